@@ -0,0 +1,449 @@
+//! Validated OSC addresses.
+
+use std::error::Error;
+use std::fmt;
+use std::str::{self, FromStr};
+use serde;
+
+/// Characters which may not appear within any component of an OSC address.
+/// ('/' is also forbidden, as it delimits the components.) This is also used
+/// by `#[derive(OscMessage)]` to validate addresses at compile time.
+#[doc(hidden)]
+pub const FORBIDDEN_CHARS: &str = " #*,/?[]{}";
+
+/// An OSC address, e.g. "/renderer/42/say".
+///
+/// An `OscAddress` always begins with '/', and each of its components (i.e.
+/// the text between two adjacent '/') is non-empty and free of the characters
+/// that OSC reserves for address patterns: space, `#`, `*`, `,`, `?`, `[`, `]`,
+/// `{` and `}`. The root address "/" has no components.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OscAddress {
+    address: String,
+}
+
+/// A validated OSC address which borrows from the deserializer's input when
+/// possible, so that routing a message needn't allocate. This is used by
+/// `#[derive(OscMessage)]`.
+///
+/// Deserializers which can't lend out their input, e.g. serde_osc, pass the
+/// address to `visit_str` instead; such addresses are copied into an inline
+/// buffer, and only allocate if they are longer than `INLINE_ADDRESS_LEN` bytes.
+#[doc(hidden)]
+#[derive(Clone, Debug)]
+pub struct BorrowedAddress<'a> {
+    address: AddressStorage<'a>,
+}
+
+/// Longest address which `BorrowedAddress` can hold without allocating, when
+/// the deserializer doesn't allow borrowing it.
+const INLINE_ADDRESS_LEN: usize = 64;
+
+#[derive(Clone, Debug)]
+enum AddressStorage<'a> {
+    Borrowed(&'a str),
+    /// The first `len` bytes of the buffer hold the address.
+    Inline([u8; INLINE_ADDRESS_LEN], usize),
+    Owned(String),
+}
+
+/// Formats a path argument with a custom function in place of its `Display`
+/// impl. This is used by `#[derive(OscMessage)]` for `format_with`.
+#[doc(hidden)]
+pub struct DisplayWith<'a, T: 'a + ?Sized>(pub &'a T, pub fn(&T, &mut fmt::Formatter) -> fmt::Result);
+
+/// Writes an OSC address component by component into any `fmt::Write`,
+/// e.g. a reusable `String` or a fixed-size buffer, validating each component
+/// as it is written.
+///
+/// This is what [`OscMessage::build_address`](trait.OscMessage.html#tymethod.build_address)
+/// writes into.
+#[derive(Debug)]
+pub struct OscAddressWriter<W> {
+    writer: W,
+    /// Number of bytes written so far.
+    len: usize,
+}
+
+/// Reason that a string is not a valid OSC address, or could not be written.
+/// Offsets are byte offsets into the full address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OscAddressError {
+    /// The address does not begin with '/'.
+    MissingLeadingSlash,
+    /// The address contains an empty component, e.g. "/a//b" or "/a/".
+    EmptyComponent(usize),
+    /// The character at the given offset may not appear in an OSC address.
+    ForbiddenChar(char, usize),
+    /// The underlying `fmt::Write` failed, e.g. because a fixed-size buffer
+    /// ran out of space.
+    WriteFailed,
+}
+
+/// Adapter which validates the text of a single component as it is written.
+struct ComponentValidator<'a, W: 'a> {
+    writer: &'a mut W,
+    /// Offset of the component within the full address.
+    offset: usize,
+    /// Number of bytes of the component written so far.
+    len: usize,
+    error: Option<OscAddressError>,
+}
+
+impl OscAddress {
+    /// Validate an OSC address.
+    pub fn new<S: Into<String>>(address: S) -> Result<Self, OscAddressError> {
+        let address = address.into();
+        validate_address(&address)?;
+        Ok(Self{ address })
+    }
+    /// Wrap a string which is already known to be a valid address.
+    pub(crate) fn from_validated(address: String) -> Self {
+        Self{ address }
+    }
+    /// The root address, "/".
+    pub fn root() -> Self {
+        Self{ address: "/".to_owned() }
+    }
+    /// Whether this is the root address, "/".
+    pub fn is_root(&self) -> bool {
+        self.address == "/"
+    }
+    /// The address, e.g. "/renderer/42/say".
+    pub fn as_str(&self) -> &str {
+        &self.address
+    }
+    /// Convert the address into a `String`.
+    pub fn into_string(self) -> String {
+        self.address
+    }
+    /// Iterate over the components of the address, e.g. "renderer", "42", "say".
+    pub fn components(&self) -> impl DoubleEndedIterator<Item=&str> {
+        // The root address "/" has no components, so split_terminator is used
+        // to avoid yielding an empty component for it.
+        self.address[1..].split_terminator('/')
+    }
+    /// Split the address into its first component and the address of the
+    /// remainder, e.g. "/renderer/42/say" -> ("renderer", "/42/say").
+    /// Returns `None` for the root address.
+    pub fn split_first(&self) -> Option<(&str, OscAddress)> {
+        if self.is_root() {
+            return None;
+        }
+        Some(match self.address[1..].find('/') {
+            None => (&self.address[1..], Self::root()),
+            Some(idx) => (&self.address[1..1+idx], Self{ address: self.address[1+idx..].to_owned() }),
+        })
+    }
+    /// The address with its last component removed, e.g. "/renderer/42/say" -> "/renderer/42".
+    /// Returns `None` for the root address.
+    pub fn parent(&self) -> Option<OscAddress> {
+        if self.is_root() {
+            return None;
+        }
+        Some(match self.address.rfind('/') {
+            Some(idx) if idx != 0 => Self{ address: self.address[..idx].to_owned() },
+            _ => Self::root(),
+        })
+    }
+    /// The address with one more component appended, e.g. "/renderer" + "42" -> "/renderer/42".
+    pub fn join(&self, component: &str) -> Result<OscAddress, OscAddressError> {
+        let mut child = self.clone();
+        child.push(component)?;
+        Ok(child)
+    }
+    /// Append a component to the address, e.g. "/renderer" + "42" -> "/renderer/42".
+    /// The address is left unmodified if the component is invalid.
+    pub fn push(&mut self, component: &str) -> Result<(), OscAddressError> {
+        let offset = if self.is_root() { 1 } else { self.address.len() + 1 };
+        validate_component(component, offset)?;
+        if !self.is_root() {
+            self.address.push('/');
+        }
+        self.address.push_str(component);
+        Ok(())
+    }
+}
+
+/// Ensure an address begins with '/' and that each of its components is valid.
+fn validate_address(address: &str) -> Result<(), OscAddressError> {
+    if !address.starts_with('/') {
+        return Err(OscAddressError::MissingLeadingSlash);
+    }
+    if address != "/" {
+        let mut offset = 1;
+        for component in address[1..].split('/') {
+            validate_component(component, offset)?;
+            offset += component.len() + 1;
+        }
+    }
+    Ok(())
+}
+
+/// Ensure a single component of an address is non-empty and contains no
+/// forbidden characters. `offset` is the component's offset in the full address.
+fn validate_component(component: &str, offset: usize) -> Result<(), OscAddressError> {
+    if component.is_empty() {
+        return Err(OscAddressError::EmptyComponent(offset));
+    }
+    match component.char_indices().find(|&(_, c)| FORBIDDEN_CHARS.contains(c)) {
+        Some((idx, c)) => Err(OscAddressError::ForbiddenChar(c, offset + idx)),
+        None => Ok(()),
+    }
+}
+
+/// Match an address component against one component of a template, such as
+/// "ch{}", given the literal text around each placeholder (e.g. `["ch", ""]`),
+/// and store the text of each placeholder in `fields`, without allocating.
+///
+/// Each placeholder matches the shortest non-empty text which is followed by
+/// the next literal. This is also used by `#[derive(OscMessage)]`, to find
+/// which literal addresses a format-string address shadows.
+#[doc(hidden)]
+pub fn match_component<'a>(component: &'a str, literals: &[&str], fields: &mut [&'a str]) -> bool {
+    let (first, rest) = match literals.split_first() {
+        None => return false,
+        Some(split) => split,
+    };
+    if !component.starts_with(first) || rest.len() != fields.len() {
+        return false;
+    }
+    let mut remainder = &component[first.len()..];
+    for (i, literal) in rest.iter().enumerate() {
+        // The final placeholder extends up to the trailing literal.
+        let end = if i + 1 == rest.len() {
+            if remainder.ends_with(literal) { Some(remainder.len() - literal.len()) } else { None }
+        } else {
+            // Skip the first character, so that the placeholder is non-empty.
+            let skip = remainder.chars().next().map_or(0, char::len_utf8);
+            remainder[skip..].find(literal).map(|idx| idx + skip)
+        };
+        match end {
+            Some(end) if end > 0 => {
+                fields[i] = &remainder[..end];
+                remainder = &remainder[end + literal.len()..];
+            },
+            _ => return false,
+        }
+    }
+    remainder.is_empty()
+}
+
+/// Match the leading components of an address against a template such as
+/// "grid/{}/{}/press", given the literal text around the placeholders of each
+/// component (e.g. `[&["grid"], &["", ""], &["", ""], &["press"]]`).
+///
+/// The text of each placeholder is stored in `fields`, in order, and the
+/// unmatched remainder of the address is returned. This is used by
+/// `#[derive(OscMessage)]`.
+#[doc(hidden)]
+pub fn match_template<'a>(address: &'a str, template: &[&[&str]], fields: &mut [&'a str]) -> Option<&'a str> {
+    let mut remainder = address;
+    let mut field_idx = 0;
+    for literals in template {
+        if !remainder.starts_with('/') {
+            return None;
+        }
+        let (component, downstream) = match remainder[1..].find('/') {
+            None => (&remainder[1..], ""),
+            Some(idx) => (&remainder[1..1+idx], &remainder[1+idx..]),
+        };
+        let num_fields = literals.len().saturating_sub(1);
+        let component_fields = fields.get_mut(field_idx..field_idx + num_fields)?;
+        if !match_component(component, literals, component_fields) {
+            return None;
+        }
+        field_idx += num_fields;
+        remainder = downstream;
+    }
+    Some(remainder)
+}
+
+impl<W: fmt::Write> OscAddressWriter<W> {
+    /// Begin writing an address into `writer`.
+    pub fn new(writer: W) -> Self {
+        Self{ writer, len: 0 }
+    }
+    /// Number of bytes written so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Whether no components have been written yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Append a component to the address, e.g. "renderer".
+    /// On error, the writer may hold a partially-written component.
+    pub fn push(&mut self, component: &str) -> Result<(), OscAddressError> {
+        validate_component(component, self.len + 1)?;
+        self.writer.write_char('/').map_err(|_| OscAddressError::WriteFailed)?;
+        self.writer.write_str(component).map_err(|_| OscAddressError::WriteFailed)?;
+        self.len += 1 + component.len();
+        Ok(())
+    }
+    /// Append every component of `address`, e.g. "/foo/bar". Appending the
+    /// root address, "/", appends nothing.
+    /// On error, the writer may hold some of the components.
+    pub fn push_address(&mut self, address: &str) -> Result<(), OscAddressError> {
+        if !address.starts_with('/') {
+            return Err(OscAddressError::MissingLeadingSlash);
+        }
+        if address != "/" {
+            for component in address[1..].split('/') {
+                self.push(component)?;
+            }
+        }
+        Ok(())
+    }
+    /// Append a component to the address by formatting `component`, without
+    /// first formatting it into a temporary `String`.
+    /// On error, the writer may hold a partially-written component.
+    pub fn push_display<T: fmt::Display + ?Sized>(&mut self, component: &T) -> Result<(), OscAddressError> {
+        self.writer.write_char('/').map_err(|_| OscAddressError::WriteFailed)?;
+        let offset = self.len + 1;
+        let mut validator = ComponentValidator{ writer: &mut self.writer, offset, len: 0, error: None };
+        let result = fmt::write(&mut validator, format_args!("{}", component));
+        let (len, error) = (validator.len, validator.error);
+        match (result, error) {
+            (Err(_), Some(error)) => Err(error),
+            (Err(_), None) => Err(OscAddressError::WriteFailed),
+            (Ok(_), _) if len == 0 => Err(OscAddressError::EmptyComponent(offset)),
+            (Ok(_), _) => {
+                self.len = offset + len;
+                Ok(())
+            },
+        }
+    }
+    /// Finish writing the address and return the underlying writer.
+    /// If no components were written, this writes the root address, "/".
+    pub fn finish(mut self) -> Result<W, OscAddressError> {
+        if self.len == 0 {
+            self.writer.write_char('/').map_err(|_| OscAddressError::WriteFailed)?;
+        }
+        Ok(self.writer)
+    }
+}
+
+impl<'a, W: fmt::Write> fmt::Write for ComponentValidator<'a, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if let Some((idx, c)) = s.char_indices().find(|&(_, c)| FORBIDDEN_CHARS.contains(c)) {
+            self.error = Some(OscAddressError::ForbiddenChar(c, self.offset + self.len + idx));
+            return Err(fmt::Error);
+        }
+        self.writer.write_str(s)?;
+        self.len += s.len();
+        Ok(())
+    }
+}
+
+impl FromStr for OscAddress {
+    type Err = OscAddressError;
+    fn from_str(s: &str) -> Result<Self, OscAddressError> {
+        Self::new(s)
+    }
+}
+
+impl AsRef<str> for OscAddress {
+    fn as_ref(&self) -> &str {
+        &self.address
+    }
+}
+
+impl<'a> PartialEq<&'a str> for OscAddress {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.address == *other
+    }
+}
+
+impl PartialEq<str> for OscAddress {
+    fn eq(&self, other: &str) -> bool {
+        self.address == other
+    }
+}
+
+impl fmt::Display for OscAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.address)
+    }
+}
+
+impl serde::Serialize for OscAddress {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.address)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for OscAddress {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: serde::Deserializer<'de>
+    {
+        let address = String::deserialize(deserializer)?;
+        Self::new(address).map_err(serde::de::Error::custom)
+    }
+}
+
+impl<'a> BorrowedAddress<'a> {
+    /// Access the address as a string, e.g. "/renderer/42/say".
+    pub fn as_str(&self) -> &str {
+        match self.address {
+            AddressStorage::Borrowed(address) => address,
+            // Copied from a `str` in its entirety, so always valid UTF-8.
+            AddressStorage::Inline(ref buffer, len) => str::from_utf8(&buffer[..len]).expect("inline OSC address isn't UTF-8"),
+            AddressStorage::Owned(ref address) => address,
+        }
+    }
+}
+
+impl<'a, T: ?Sized> fmt::Display for DisplayWith<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (self.1)(self.0, f)
+    }
+}
+
+/// Visitor which borrows the address from the input if it can.
+struct BorrowedAddressVisitor;
+
+impl<'de> serde::de::Visitor<'de> for BorrowedAddressVisitor {
+    type Value = BorrowedAddress<'de>;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an OSC address")
+    }
+    fn visit_borrowed_str<E: serde::de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        validate_address(v).map_err(E::custom)?;
+        Ok(BorrowedAddress{ address: AddressStorage::Borrowed(v) })
+    }
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        if v.len() > INLINE_ADDRESS_LEN {
+            return self.visit_string(v.to_owned());
+        }
+        validate_address(v).map_err(E::custom)?;
+        let mut buffer = [0; INLINE_ADDRESS_LEN];
+        buffer[..v.len()].copy_from_slice(v.as_bytes());
+        Ok(BorrowedAddress{ address: AddressStorage::Inline(buffer, v.len()) })
+    }
+    fn visit_string<E: serde::de::Error>(self, v: String) -> Result<Self::Value, E> {
+        validate_address(&v).map_err(E::custom)?;
+        Ok(BorrowedAddress{ address: AddressStorage::Owned(v) })
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for BorrowedAddress<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: serde::Deserializer<'de>
+    {
+        deserializer.deserialize_str(BorrowedAddressVisitor)
+    }
+}
+
+impl fmt::Display for OscAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            OscAddressError::MissingLeadingSlash => write!(f, "OSC address must begin with '/'"),
+            OscAddressError::EmptyComponent(pos) => write!(f, "empty component at offset {} of OSC address", pos),
+            OscAddressError::ForbiddenChar(c, pos) => write!(f, "forbidden character '{}' at offset {} of OSC address", c, pos),
+            OscAddressError::WriteFailed => write!(f, "failed to write OSC address"),
+        }
+    }
+}
+
+impl Error for OscAddressError {}
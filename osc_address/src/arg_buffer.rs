@@ -0,0 +1,62 @@
+//! Owned storage for the arguments of a message, so that they may be
+//! deserialized more than once.
+
+use std::fmt;
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+
+use raw::{OscValue, ValuesDeserializer};
+
+/// The argument list of a single OSC message, captured from any
+/// self-describing serde backend.
+///
+/// An address pattern may match several messages, each with its own payload
+/// type, so the arguments are captured once and then replayed into each
+/// matched payload. Arrays are captured up to four levels deep.
+#[derive(Clone, Debug, Default)]
+pub struct ArgBuffer {
+    args: Vec<OscValue>,
+}
+
+/// Visitor that captures the argument list of a message.
+struct ArgBufferVisitor;
+
+impl<'de> Visitor<'de> for ArgBufferVisitor {
+    type Value = ArgBuffer;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of message arguments")
+    }
+    fn visit_unit<E: de::Error>(self) -> Result<ArgBuffer, E> {
+        Ok(ArgBuffer::default())
+    }
+    fn visit_seq<A>(self, mut seq: A) -> Result<ArgBuffer, A::Error>
+        where A: SeqAccess<'de>
+    {
+        let mut args = Vec::new();
+        while let Some(arg) = seq.next_element()? {
+            args.push(arg);
+        }
+        Ok(ArgBuffer{ args })
+    }
+}
+
+impl ArgBuffer {
+    /// Deserialize a payload from the captured arguments.
+    /// This may be called any number of times.
+    pub fn replay<'a, T, E>(&'a self) -> Result<T, E>
+        where T: Deserialize<'a>, E: de::Error
+    {
+        T::deserialize(ValuesDeserializer::new(&self.args))
+    }
+    /// The captured arguments.
+    pub(crate) fn values(&self) -> &[OscValue] {
+        &self.args
+    }
+}
+
+impl<'de> Deserialize<'de> for ArgBuffer {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        deserializer.deserialize_seq(ArgBufferVisitor)
+    }
+}
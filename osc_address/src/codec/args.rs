@@ -0,0 +1,42 @@
+//! Deserialization of a message's argument list, shared by the decoder and by
+//! the replay of captured arguments, so that a payload is treated alike by both.
+
+use serde::de::{self, SeqAccess, Visitor};
+
+/// The arguments of a message, or of an array within them, being deserialized
+/// as a sequence.
+pub(crate) trait ArgList<'de>: SeqAccess<'de> {
+    /// Number of arguments which haven't been visited, counting a nested array as one.
+    fn remaining(&self) -> usize;
+    /// Verify that every argument has been visited, and that nothing else was left unread.
+    fn finish(&self) -> Result<(), Self::Error>;
+}
+
+/// Deserialize the arguments as a sequence.
+pub(crate) fn deserialize_args<'de, A, V>(mut args: A, visitor: V) -> Result<V::Value, A::Error>
+    where A: ArgList<'de>, V: Visitor<'de>
+{
+    let value = visitor.visit_seq(&mut args)?;
+    // Leftover arguments mean that the payload doesn't match the message.
+    args.finish()?;
+    Ok(value)
+}
+
+/// Deserialize the arguments as a unit, which, like serde_osc, is how a
+/// message without arguments is treated.
+pub(crate) fn deserialize_args_unit<'de, A, V>(args: A, visitor: V) -> Result<V::Value, A::Error>
+    where A: ArgList<'de>, V: Visitor<'de>
+{
+    match args.remaining() {
+        0 => {
+            args.finish()?;
+            visitor.visit_unit()
+        },
+        len => Err(de::Error::invalid_length(len, &"a message without arguments")),
+    }
+}
+
+/// Error for arguments which the payload left unvisited.
+pub(crate) fn trailing_arguments<E: de::Error>(remaining: usize) -> E {
+    E::custom(format_args!("{} unexpected trailing arguments", remaining))
+}
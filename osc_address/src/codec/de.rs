@@ -0,0 +1,570 @@
+//! Deserialization of OSC packets.
+
+use std::str;
+use serde;
+use serde::de::{DeserializeSeed, Deserializer, EnumAccess, IntoDeserializer, SeqAccess, Unexpected, VariantAccess, Visitor};
+use serde::de::value::SeqDeserializer;
+
+use super::OscCodecError;
+use super::args::{deserialize_args, deserialize_args_unit, trailing_arguments, ArgList};
+use types::VALUE_NAME;
+
+/// Deserializes an entire OSC packet: either a message or a bundle.
+///
+/// A message is visited as a sequence of its address followed by a sequence
+/// of its arguments. A bundle is visited as a sequence of its `(u32, u32)`
+/// time tag followed by a sequence of the packets it contains. This mirrors
+/// the behavior of `PacketSerializer`.
+pub struct PacketDeserializer<'de> {
+    input: &'de [u8],
+}
+
+/// Visits the address and the argument list of a message.
+struct MessageAccess<'de> {
+    address: Option<&'de str>,
+    args: Option<ArgsDeserializer<'de>>,
+}
+
+/// Deserializes the arguments of a message, as a sequence.
+struct ArgsDeserializer<'de> {
+    /// The remaining type tags, without the leading ','.
+    tags: &'de [u8],
+    reader: Reader<'de>,
+}
+
+/// Deserializes an array within the arguments of a message, as a sequence.
+/// The opening '[' has already been consumed.
+struct ArrayDeserializer<'a, 'de: 'a> {
+    args: &'a mut ArgsDeserializer<'de>,
+}
+
+/// A single decoded message argument, other than an array.
+enum Arg<'de> {
+    Int(i32),
+    Float(f32),
+    Str(&'de str),
+    Blob(&'de [u8]),
+    Bool(bool),
+    Nil,
+    Impulse,
+    Int64(i64),
+    Double(f64),
+    Char(char),
+    Color(u32),
+    Midi(&'de [u8]),
+    TimeTag(u64),
+    Symbol(&'de str),
+}
+
+/// Presents an argument as an enum variant named by its type tag, for an
+/// `OscValue` which asked for it.
+struct TaggedArg<'de> {
+    tag: u8,
+    arg: Arg<'de>,
+}
+
+/// Visits the time tag and the elements of a bundle.
+struct BundleAccess<'de> {
+    time_tag: Option<(u32, u32)>,
+    elements: Option<Reader<'de>>,
+}
+
+/// Deserializes each size-prefixed element of a bundle, as a sequence of packets.
+struct ElementsDeserializer<'de> {
+    reader: Reader<'de>,
+}
+
+/// Cursor over OSC-encoded data.
+struct Reader<'de> {
+    data: &'de [u8],
+}
+
+impl<'de> PacketDeserializer<'de> {
+    /// Deserialize the packet which occupies the entirety of `input`.
+    pub fn new(input: &'de [u8]) -> Self {
+        Self{ input }
+    }
+}
+
+impl<'de> Deserializer<'de> for PacketDeserializer<'de> {
+    type Error = OscCodecError;
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        let mut reader = Reader{ data: self.input };
+        let address = reader.read_str()?;
+        if address == "#bundle" {
+            let time_tag = (reader.read_u32()?, reader.read_u32()?);
+            visitor.visit_seq(BundleAccess{ time_tag: Some(time_tag), elements: Some(reader) })
+        } else if address.starts_with('/') {
+            let args = ArgsDeserializer::new(reader)?;
+            let mut access = MessageAccess{ address: Some(address), args: Some(args) };
+            let value = visitor.visit_seq(&mut access)?;
+            // The arguments must be consumed even if they weren't visited.
+            match access.args {
+                Some(args) => args.finish().map(|()| value),
+                None => Ok(value),
+            }
+        } else {
+            Err(OscCodecError::BadFormat)
+        }
+    }
+    // The packet is self-describing; ignore all type hints.
+    forward_to_deserialize_any! {
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string unit option
+        seq bytes byte_buf map unit_struct newtype_struct
+        tuple_struct struct identifier tuple enum ignored_any
+    }
+}
+
+impl<'de> SeqAccess<'de> for MessageAccess<'de> {
+    type Error = OscCodecError;
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, OscCodecError>
+        where T: DeserializeSeed<'de>
+    {
+        if let Some(address) = self.address.take() {
+            return seed.deserialize(Arg::Str(address)).map(Some);
+        }
+        match self.args.take() {
+            Some(args) => seed.deserialize(args).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<'de> ArgsDeserializer<'de> {
+    /// Begin reading the arguments of a message, given the data which follows
+    /// its address.
+    fn new(mut reader: Reader<'de>) -> Result<Self, OscCodecError> {
+        // Some older implementations omit the type tag string of a message
+        // without arguments.
+        if reader.data.is_empty() {
+            return Ok(Self{ tags: &[], reader });
+        }
+        let tags = reader.read_padded_bytes()?;
+        match tags.split_first() {
+            Some((&b',', tags)) => Ok(Self{ tags, reader }),
+            _ => Err(OscCodecError::BadFormat),
+        }
+    }
+    /// Deserialize the next argument, which may be an array. There must be one.
+    fn next_element<T>(&mut self, tag: u8, seed: T) -> Result<T::Value, OscCodecError>
+        where T: DeserializeSeed<'de>
+    {
+        self.tags = &self.tags[1..];
+        if tag == b'[' {
+            seed.deserialize(ArrayDeserializer{ args: self })
+        } else {
+            seed.deserialize(TaggedArg{ tag, arg: self.read_arg(tag)? })
+        }
+    }
+    /// Read the data of an argument which is not an array, given its type tag.
+    fn read_arg(&mut self, tag: u8) -> Result<Arg<'de>, OscCodecError> {
+        Ok(match tag {
+            b'i' => Arg::Int(self.reader.read_i32()?),
+            b'f' => Arg::Float(self.reader.read_f32()?),
+            b's' => Arg::Str(self.reader.read_str()?),
+            b'b' => Arg::Blob(self.reader.read_blob()?),
+            b'T' => Arg::Bool(true),
+            b'F' => Arg::Bool(false),
+            b'N' => Arg::Nil,
+            b'I' => Arg::Impulse,
+            b'h' => Arg::Int64(self.reader.read_u64()? as i64),
+            b'd' => Arg::Double(f64::from_bits(self.reader.read_u64()?)),
+            b'c' => Arg::Char(char::from_u32(self.reader.read_u32()?).ok_or(OscCodecError::BadFormat)?),
+            b'r' => Arg::Color(self.reader.read_u32()?),
+            b'm' => Arg::Midi(self.reader.read_bytes(4)?),
+            b't' => Arg::TimeTag(self.reader.read_u64()?),
+            b'S' => Arg::Symbol(self.reader.read_str()?),
+            _ => return Err(OscCodecError::UnknownTypeTag(tag as char)),
+        })
+    }
+    /// Return an error unless the next argument is the ']' which closes the
+    /// current array (or, at the top level, unless the arguments have ended).
+    fn expect_end(&self) -> Result<(), OscCodecError> {
+        match self.remaining() {
+            0 => Ok(()),
+            remaining => Err(trailing_arguments(remaining)),
+        }
+    }
+    /// Consume the ']' which closes an array, after all of its elements.
+    fn close_array(&mut self) -> Result<(), OscCodecError> {
+        self.expect_end()?;
+        match self.tags.split_first() {
+            Some((&b']', tags)) => {
+                self.tags = tags;
+                Ok(())
+            },
+            _ => Err(OscCodecError::BadFormat),
+        }
+    }
+}
+
+impl<'de> ArgList<'de> for ArgsDeserializer<'de> {
+    /// The arguments before the ']' which closes the current array, or, at the
+    /// top level, before the arguments end.
+    fn remaining(&self) -> usize {
+        let mut depth = 0;
+        let mut remaining = 0;
+        for &tag in self.tags {
+            match tag {
+                b']' if depth == 0 => break,
+                b']' => depth -= 1,
+                b'[' => {
+                    if depth == 0 {
+                        remaining += 1;
+                    }
+                    depth += 1;
+                },
+                _ if depth == 0 => remaining += 1,
+                _ => {},
+            }
+        }
+        remaining
+    }
+    fn finish(&self) -> Result<(), OscCodecError> {
+        self.expect_end()?;
+        // Either a stray ']', or data which no type tag describes.
+        if !self.tags.is_empty() || !self.reader.data.is_empty() {
+            return Err(OscCodecError::BadFormat);
+        }
+        Ok(())
+    }
+}
+
+impl<'de> Deserializer<'de> for ArgsDeserializer<'de> {
+    type Error = OscCodecError;
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        deserialize_args(self, visitor)
+    }
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        deserialize_args_unit(self, visitor)
+    }
+    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        self.deserialize_unit(visitor)
+    }
+    forward_to_deserialize_any! {
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string option
+        seq bytes byte_buf map newtype_struct
+        tuple_struct struct identifier tuple enum ignored_any
+    }
+}
+
+impl<'de> SeqAccess<'de> for ArgsDeserializer<'de> {
+    type Error = OscCodecError;
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, OscCodecError>
+        where T: DeserializeSeed<'de>
+    {
+        match self.tags.first() {
+            None => Ok(None),
+            // An array must be closed within the arguments.
+            Some(&b']') => Err(OscCodecError::BadFormat),
+            Some(&tag) => self.next_element(tag, seed).map(Some),
+        }
+    }
+}
+
+impl<'a, 'de> Deserializer<'de> for ArrayDeserializer<'a, 'de> {
+    type Error = OscCodecError;
+    fn deserialize_any<V>(mut self, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        let value = visitor.visit_seq(&mut self)?;
+        // Leftover elements mean that the payload doesn't match the message,
+        // e.g. a fixed-size array which is too short.
+        self.args.close_array()?;
+        Ok(value)
+    }
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        visitor.visit_some(self)
+    }
+    // An empty sequence of bytes is encoded as an empty array rather than a
+    // blob, as the type of its elements is unknown.
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        match self.args.tags.first() {
+            Some(&b']') => {
+                self.args.close_array()?;
+                visitor.visit_borrowed_bytes(&[])
+            },
+            _ => self.deserialize_any(visitor),
+        }
+    }
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        self.deserialize_bytes(visitor)
+    }
+    forward_to_deserialize_any! {
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string unit
+        seq map unit_struct newtype_struct
+        tuple_struct struct identifier tuple enum ignored_any
+    }
+}
+
+impl<'a, 'de> SeqAccess<'de> for ArrayDeserializer<'a, 'de> {
+    type Error = OscCodecError;
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, OscCodecError>
+        where T: DeserializeSeed<'de>
+    {
+        match self.args.tags.first() {
+            // The array is not closed.
+            None => Err(OscCodecError::BadFormat),
+            // Leave the ']' to be consumed by `close_array`.
+            Some(&b']') => Ok(None),
+            Some(&tag) => self.args.next_element(tag, seed).map(Some),
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> for TaggedArg<'de> {
+    type Error = OscCodecError;
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        self.arg.deserialize_any(visitor)
+    }
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        self.arg.deserialize_option(visitor)
+    }
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        self.arg.deserialize_unit(visitor)
+    }
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        if name == VALUE_NAME {
+            visitor.visit_enum(self)
+        } else {
+            self.arg.deserialize_any(visitor)
+        }
+    }
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        self.arg.deserialize_seq(visitor)
+    }
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        self.arg.deserialize_tuple(len, visitor)
+    }
+    forward_to_deserialize_any! {
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string
+        bytes byte_buf map unit_struct
+        tuple_struct struct identifier enum ignored_any
+    }
+}
+
+impl<'de> EnumAccess<'de> for TaggedArg<'de> {
+    type Error = OscCodecError;
+    type Variant = Arg<'de>;
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Arg<'de>), OscCodecError>
+        where V: DeserializeSeed<'de>
+    {
+        let tag = seed.deserialize((self.tag as char).into_deserializer())?;
+        Ok((tag, self.arg))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Arg<'de> {
+    type Error = OscCodecError;
+    fn unit_variant(self) -> Result<(), OscCodecError> {
+        Ok(())
+    }
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, OscCodecError>
+        where T: DeserializeSeed<'de>
+    {
+        seed.deserialize(self)
+    }
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        Err(serde::de::Error::invalid_type(Unexpected::NewtypeVariant, &"a tuple variant"))
+    }
+    fn struct_variant<V>(self, _fields: &'static [&'static str], _visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        Err(serde::de::Error::invalid_type(Unexpected::NewtypeVariant, &"a struct variant"))
+    }
+}
+
+impl<'de> Deserializer<'de> for Arg<'de> {
+    type Error = OscCodecError;
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        match self {
+            Arg::Int(v) => visitor.visit_i32(v),
+            Arg::Float(v) => visitor.visit_f32(v),
+            Arg::Str(v) => visitor.visit_borrowed_str(v),
+            Arg::Blob(v) => visitor.visit_borrowed_bytes(v),
+            Arg::Bool(v) => visitor.visit_bool(v),
+            Arg::Nil => visitor.visit_none(),
+            Arg::Impulse => visitor.visit_unit(),
+            Arg::Int64(v) => visitor.visit_i64(v),
+            Arg::Double(v) => visitor.visit_f64(v),
+            Arg::Char(v) => visitor.visit_char(v),
+            Arg::Color(v) => visitor.visit_u32(v),
+            Arg::Midi(v) => visitor.visit_borrowed_bytes(v),
+            Arg::TimeTag(v) => visitor.visit_u64(v),
+            Arg::Symbol(v) => visitor.visit_borrowed_str(v),
+        }
+    }
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        match self {
+            Arg::Nil => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        match self {
+            Arg::Nil => visitor.visit_unit(),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+    // A sequence of bytes, e.g. a `Vec<u8>`, is encoded as a blob.
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        match self {
+            Arg::Blob(v) => SeqDeserializer::new(v.iter().cloned()).deserialize_any(visitor),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        self.deserialize_seq(visitor)
+    }
+    // OSC arguments are strongly typed; ignore all other type hints.
+    forward_to_deserialize_any! {
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string
+        bytes byte_buf map unit_struct newtype_struct
+        tuple_struct struct identifier enum ignored_any
+    }
+}
+
+impl<'de> SeqAccess<'de> for BundleAccess<'de> {
+    type Error = OscCodecError;
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, OscCodecError>
+        where T: DeserializeSeed<'de>
+    {
+        if let Some((sec, frac)) = self.time_tag.take() {
+            let time_tag = [sec, frac];
+            let time_tag: SeqDeserializer<_, OscCodecError> = SeqDeserializer::new(time_tag.iter().cloned());
+            return seed.deserialize(time_tag).map(Some);
+        }
+        match self.elements.take() {
+            Some(reader) => seed.deserialize(ElementsDeserializer{ reader }).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> for ElementsDeserializer<'de> {
+    type Error = OscCodecError;
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, OscCodecError>
+        where V: Visitor<'de>
+    {
+        visitor.visit_seq(self)
+    }
+    forward_to_deserialize_any! {
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string unit option
+        seq bytes byte_buf map unit_struct newtype_struct
+        tuple_struct struct identifier tuple enum ignored_any
+    }
+}
+
+impl<'de> SeqAccess<'de> for ElementsDeserializer<'de> {
+    type Error = OscCodecError;
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, OscCodecError>
+        where T: DeserializeSeed<'de>
+    {
+        if self.reader.data.is_empty() {
+            return Ok(None);
+        }
+        let size = self.reader.read_len()?;
+        if size % 4 != 0 {
+            return Err(OscCodecError::BadFormat);
+        }
+        let element = self.reader.read_bytes(size)?;
+        seed.deserialize(PacketDeserializer::new(element)).map(Some)
+    }
+}
+
+impl<'de> Reader<'de> {
+    fn read_bytes(&mut self, len: usize) -> Result<&'de [u8], OscCodecError> {
+        if self.data.len() < len {
+            return Err(OscCodecError::UnexpectedEof);
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+    fn read_u32(&mut self) -> Result<u32, OscCodecError> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+    fn read_u64(&mut self) -> Result<u64, OscCodecError> {
+        Ok(((self.read_u32()? as u64) << 32) | self.read_u32()? as u64)
+    }
+    fn read_i32(&mut self) -> Result<i32, OscCodecError> {
+        self.read_u32().map(|v| v as i32)
+    }
+    fn read_f32(&mut self) -> Result<f32, OscCodecError> {
+        self.read_u32().map(f32::from_bits)
+    }
+    /// Read the length of a blob or bundle element, which OSC represents as an `i32`.
+    fn read_len(&mut self) -> Result<usize, OscCodecError> {
+        let len = self.read_i32()?;
+        if len < 0 {
+            return Err(OscCodecError::BadFormat);
+        }
+        Ok(len as usize)
+    }
+    /// Read a NUL-terminated sequence of bytes and verify its padding.
+    fn read_padded_bytes(&mut self) -> Result<&'de [u8], OscCodecError> {
+        let len = match self.data.iter().position(|&b| b == 0) {
+            None => return Err(OscCodecError::UnexpectedEof),
+            Some(len) => len,
+        };
+        // The terminating NUL is followed by up to 3 more, to reach a multiple of 4 bytes.
+        let padded = self.read_bytes((len + 4) & !3)
+            .map_err(|_| OscCodecError::BadPadding)?;
+        if padded[len..].iter().any(|&b| b != 0) {
+            return Err(OscCodecError::BadPadding);
+        }
+        Ok(&padded[..len])
+    }
+    fn read_str(&mut self) -> Result<&'de str, OscCodecError> {
+        let bytes = self.read_padded_bytes()?;
+        str::from_utf8(bytes).map_err(|_| OscCodecError::BadUtf8)
+    }
+    /// Read a size-prefixed blob and verify its padding.
+    fn read_blob(&mut self) -> Result<&'de [u8], OscCodecError> {
+        let len = self.read_len()?;
+        let padded = self.read_bytes((len + 3) & !3)?;
+        if padded[len..].iter().any(|&b| b != 0) {
+            return Err(OscCodecError::BadPadding);
+        }
+        Ok(&padded[..len])
+    }
+}
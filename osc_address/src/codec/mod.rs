@@ -0,0 +1,95 @@
+//! Native encoding and decoding of the OSC 1.0 binary format.
+//!
+//! The data model is the same as that of [serde_osc]: a message is a sequence
+//! of its address followed by a sequence of its arguments, and a bundle is a
+//! sequence of its `(u32, u32)` time tag followed by a sequence of the packets
+//! it contains. Any [`OscMessage`](../trait.OscMessage.html) or
+//! [`OscPacket`](../enum.OscPacket.html) follows this model.
+//!
+//! [serde_osc]: https://crates.io/crates/serde_osc
+
+pub(crate) mod args;
+mod de;
+mod ser;
+
+use std::error::Error;
+use std::fmt;
+use serde;
+
+/// Serialize `value` into a single OSC packet: a message or a bundle.
+///
+/// Message arguments are encoded according to their serde type: `i32` as 'i',
+/// `f32` as 'f', strings as 's', byte arrays and sequences of `u8` such as a
+/// `&[u8]` as 'b', `bool` as 'T' or 'F', `i64` as 'h', `f64` as 'd', `char` as
+/// 'c', and `None` or `()` as 'N'.
+/// The remaining OSC 1.1 types are encoded via the wrapper types of this
+/// crate, e.g. [`OscMidi`](struct.OscMidi.html) as 'm'. Sequences within the
+/// arguments, such as a `Vec<T>` or `[T; N]`, are encoded as OSC arrays
+/// delimited by '[' and ']', and may be nested.
+///
+/// Unlike serde_osc, the packet is not prefixed by its length, as OSC 1.0
+/// expects of a datagram transport such as UDP. Stream transports may frame
+/// the packet as they see fit.
+pub fn encode<T: serde::Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, OscCodecError> {
+    let mut output = Vec::new();
+    value.serialize(ser::PacketSerializer::new(&mut output))?;
+    Ok(output)
+}
+
+/// Deserialize a single OSC packet (a message or a bundle) which occupies
+/// the entirety of `packet`.
+///
+/// Strings and blobs are borrowed from `packet` where the target type allows it.
+pub fn decode<'de, T: serde::Deserialize<'de>>(packet: &'de [u8]) -> Result<T, OscCodecError> {
+    T::deserialize(de::PacketDeserializer::new(packet))
+}
+
+/// Error encountered while encoding or decoding OSC packets.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OscCodecError {
+    /// Error reported by the type being (de)serialized, e.g. an invalid address.
+    Message(String),
+    /// The value has no representation in OSC, e.g. a map, or an argument of
+    /// a type that OSC does not define.
+    UnsupportedType(&'static str),
+    /// The packet contains an argument with a type tag that is not understood.
+    UnknownTypeTag(char),
+    /// The packet ended before the data it describes.
+    UnexpectedEof,
+    /// A string or blob is not padded with zeros to a multiple of 4 bytes.
+    BadPadding,
+    /// A string is not valid UTF-8.
+    BadUtf8,
+    /// The packet is neither a well-formed message nor a well-formed bundle,
+    /// e.g. because its address does not begin with '/' or its type tag
+    /// string does not begin with ','.
+    BadFormat,
+}
+
+impl fmt::Display for OscCodecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            OscCodecError::Message(ref msg) => f.write_str(msg),
+            OscCodecError::UnsupportedType(ty) => write!(f, "{} cannot be represented in OSC", ty),
+            OscCodecError::UnknownTypeTag(tag) => write!(f, "unknown OSC type tag '{}'", tag),
+            OscCodecError::UnexpectedEof => write!(f, "unexpected end of OSC packet"),
+            OscCodecError::BadPadding => write!(f, "OSC data is not zero-padded to a multiple of 4 bytes"),
+            OscCodecError::BadUtf8 => write!(f, "OSC string is not valid UTF-8"),
+            OscCodecError::BadFormat => write!(f, "malformed OSC packet"),
+        }
+    }
+}
+
+impl Error for OscCodecError {}
+
+impl serde::ser::Error for OscCodecError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        OscCodecError::Message(msg.to_string())
+    }
+}
+
+impl serde::de::Error for OscCodecError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        OscCodecError::Message(msg.to_string())
+    }
+}
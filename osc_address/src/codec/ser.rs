@@ -0,0 +1,665 @@
+//! Serialization of OSC packets.
+
+use std::convert::TryInto;
+use serde::ser::{Impossible, Serialize, Serializer, SerializeSeq, SerializeStruct, SerializeTuple, SerializeTupleStruct};
+
+use types::{COLOR_NAME, IMPULSE_NAME, MIDI_NAME, SYMBOL_NAME, TIME_TAG_NAME};
+use super::OscCodecError;
+
+/// Implement the listed `Serializer` methods as failing with
+/// `OscCodecError::UnsupportedType`.
+macro_rules! unsupported {
+    ($($method:ident)*) => {
+        $( unsupported!(@method $method); )*
+    };
+    (@method bool) => { unsupported!(@prim serialize_bool bool); };
+    (@method i8) => { unsupported!(@prim serialize_i8 i8); };
+    (@method i16) => { unsupported!(@prim serialize_i16 i16); };
+    (@method i32) => { unsupported!(@prim serialize_i32 i32); };
+    (@method i64) => { unsupported!(@prim serialize_i64 i64); };
+    (@method u8) => { unsupported!(@prim serialize_u8 u8); };
+    (@method u16) => { unsupported!(@prim serialize_u16 u16); };
+    (@method u32) => { unsupported!(@prim serialize_u32 u32); };
+    (@method u64) => { unsupported!(@prim serialize_u64 u64); };
+    (@method f32) => { unsupported!(@prim serialize_f32 f32); };
+    (@method f64) => { unsupported!(@prim serialize_f64 f64); };
+    (@method char) => { unsupported!(@prim serialize_char char); };
+    (@method str) => { unsupported!(@prim serialize_str &str); };
+    (@method bytes) => { unsupported!(@prim serialize_bytes &[u8]); };
+    (@method none) => {
+        fn serialize_none(self) -> Result<Self::Ok, OscCodecError> {
+            Err(OscCodecError::UnsupportedType("Option"))
+        }
+    };
+    (@method some) => {
+        fn serialize_some<T: ?Sized + Serialize>(self, _: &T) -> Result<Self::Ok, OscCodecError> {
+            Err(OscCodecError::UnsupportedType("Option"))
+        }
+    };
+    (@method unit) => {
+        fn serialize_unit(self) -> Result<Self::Ok, OscCodecError> {
+            Err(OscCodecError::UnsupportedType("()"))
+        }
+    };
+    (@method unit_struct) => {
+        fn serialize_unit_struct(self, _: &'static str) -> Result<Self::Ok, OscCodecError> {
+            Err(OscCodecError::UnsupportedType("unit struct"))
+        }
+    };
+    (@method unit_variant) => {
+        fn serialize_unit_variant(self, _: &'static str, _: u32, _: &'static str) -> Result<Self::Ok, OscCodecError> {
+            Err(OscCodecError::UnsupportedType("enum"))
+        }
+    };
+    (@method newtype_variant) => {
+        fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _: &'static str, _: u32, _: &'static str, _: &T) -> Result<Self::Ok, OscCodecError> {
+            Err(OscCodecError::UnsupportedType("enum"))
+        }
+    };
+    (@method seq) => {
+        fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, OscCodecError> {
+            Err(OscCodecError::UnsupportedType("sequence"))
+        }
+    };
+    (@method tuple) => {
+        fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, OscCodecError> {
+            Err(OscCodecError::UnsupportedType("tuple"))
+        }
+    };
+    (@method tuple_struct) => {
+        fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeTupleStruct, OscCodecError> {
+            Err(OscCodecError::UnsupportedType("tuple struct"))
+        }
+    };
+    (@method tuple_variant) => {
+        fn serialize_tuple_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self::SerializeTupleVariant, OscCodecError> {
+            Err(OscCodecError::UnsupportedType("enum"))
+        }
+    };
+    (@method map) => {
+        fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, OscCodecError> {
+            Err(OscCodecError::UnsupportedType("map"))
+        }
+    };
+    (@method struct) => {
+        fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct, OscCodecError> {
+            Err(OscCodecError::UnsupportedType("struct"))
+        }
+    };
+    (@method struct_variant) => {
+        fn serialize_struct_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self::SerializeStructVariant, OscCodecError> {
+            Err(OscCodecError::UnsupportedType("enum"))
+        }
+    };
+    (@prim $method:ident $ty:ty) => {
+        fn $method(self, _: $ty) -> Result<Self::Ok, OscCodecError> {
+            Err(OscCodecError::UnsupportedType(stringify!($ty)))
+        }
+    };
+}
+
+/// Implement `SerializeSeq`, `SerializeTuple`, `SerializeStruct` and
+/// `SerializeTupleStruct` for a type in terms of its `serialize_element` and
+/// `end` methods, as OSC does not distinguish between them.
+macro_rules! impl_serialize_seq_like {
+    ($ty:ident) => {
+        impl<'a> SerializeSeq for $ty<'a> {
+            type Ok = ();
+            type Error = OscCodecError;
+            fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), OscCodecError> {
+                $ty::serialize_element(self, value)
+            }
+            fn end(self) -> Result<(), OscCodecError> {
+                $ty::end(self)
+            }
+        }
+        impl<'a> SerializeTuple for $ty<'a> {
+            type Ok = ();
+            type Error = OscCodecError;
+            fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), OscCodecError> {
+                $ty::serialize_element(self, value)
+            }
+            fn end(self) -> Result<(), OscCodecError> {
+                $ty::end(self)
+            }
+        }
+        impl<'a> SerializeTupleStruct for $ty<'a> {
+            type Ok = ();
+            type Error = OscCodecError;
+            fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), OscCodecError> {
+                $ty::serialize_element(self, value)
+            }
+            fn end(self) -> Result<(), OscCodecError> {
+                $ty::end(self)
+            }
+        }
+        impl<'a> SerializeStruct for $ty<'a> {
+            type Ok = ();
+            type Error = OscCodecError;
+            fn serialize_field<T: ?Sized + Serialize>(&mut self, _key: &'static str, value: &T) -> Result<(), OscCodecError> {
+                $ty::serialize_element(self, value)
+            }
+            fn end(self) -> Result<(), OscCodecError> {
+                $ty::end(self)
+            }
+        }
+    };
+}
+
+/// Serializes an entire OSC packet: either a message or a bundle.
+///
+/// The packet is serialized from a sequence (or tuple, or struct). If its
+/// first element is a string, then that is the address of a message, and each
+/// remaining element is a sequence of the message's arguments. If instead its
+/// first element is a `(u32, u32)` time tag, then the packet is a bundle, and
+/// each remaining element is a sequence of packets.
+pub struct PacketSerializer<'a> {
+    output: &'a mut Vec<u8>,
+}
+
+/// Serializes the elements of a packet, once the packet has been begun.
+pub struct PacketContents<'a> {
+    output: &'a mut Vec<u8>,
+    state: State,
+}
+
+enum State {
+    /// Nothing has been serialized yet.
+    Empty,
+    /// The packet is a message. Its address has been written to the output.
+    Message(MessageBuf),
+    /// The packet is a bundle. Its header and time tag have been written to the output.
+    Bundle,
+}
+
+/// What the first element of a packet revealed it to be.
+enum Head {
+    Message,
+    Bundle,
+}
+
+/// Serializes the first element of a packet: the address of a message, or
+/// the time tag of a bundle. Either is written straight to the output.
+struct HeadSerializer<'a> {
+    output: &'a mut Vec<u8>,
+}
+
+/// Serializes the two `u32` components of a bundle's time tag.
+struct TimeTagSerializer<'a> {
+    output: &'a mut Vec<u8>,
+    parts: [u32; 2],
+    len: usize,
+}
+
+/// Serializes a single component of a bundle's time tag.
+struct TimeTagPartSerializer;
+
+/// The type tags and argument data of a message, which are accumulated
+/// separately because the type tag string precedes all the arguments.
+struct MessageBuf {
+    tags: Vec<u8>,
+    args: Vec<u8>,
+}
+
+/// Serializes a single argument of a message, or, if not `nested`, a
+/// sequence of the arguments.
+struct ArgSerializer<'a> {
+    msg: &'a mut MessageBuf,
+    nested: bool,
+    /// Type tag chosen by one of the wrapper types in `types`, which is
+    /// applied to the value that the wrapper serializes.
+    tag: Option<u8>,
+    /// Where to collect a `u8`, if this is an element of an array.
+    bytes: Option<&'a mut Vec<u8>>,
+}
+
+/// Serializes each element of a sequence as a message argument.
+struct ArgListSerializer<'a> {
+    msg: &'a mut MessageBuf,
+    /// Whether the sequence is an array within the arguments, rather than
+    /// the arguments themselves.
+    array: bool,
+    /// Number of type tags which preceded the array's '['.
+    start: usize,
+    /// The `u8` elements of the array. An array of only `u8`, e.g. a `&[u8]`,
+    /// is encoded as a blob rather than as an array.
+    bytes: Vec<u8>,
+}
+
+/// Serializes a sequence of packets contained within a bundle.
+struct BundleElementsSerializer<'a> {
+    output: &'a mut Vec<u8>,
+}
+
+/// Serializes each element of a sequence as a size-prefixed bundle element.
+struct BundleElementSerializer<'a> {
+    output: &'a mut Vec<u8>,
+}
+
+impl<'a> PacketSerializer<'a> {
+    /// Serialize a packet by appending it to `output`.
+    pub fn new(output: &'a mut Vec<u8>) -> Self {
+        Self{ output }
+    }
+}
+
+impl<'a> Serializer for PacketSerializer<'a> {
+    type Ok = ();
+    type Error = OscCodecError;
+    type SerializeSeq = PacketContents<'a>;
+    type SerializeTuple = PacketContents<'a>;
+    type SerializeTupleStruct = PacketContents<'a>;
+    type SerializeTupleVariant = Impossible<(), OscCodecError>;
+    type SerializeMap = Impossible<(), OscCodecError>;
+    type SerializeStruct = PacketContents<'a>;
+    type SerializeStructVariant = Impossible<(), OscCodecError>;
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<PacketContents<'a>, OscCodecError> {
+        Ok(PacketContents{ output: self.output, state: State::Empty })
+    }
+    fn serialize_tuple(self, len: usize) -> Result<PacketContents<'a>, OscCodecError> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<PacketContents<'a>, OscCodecError> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<PacketContents<'a>, OscCodecError> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<(), OscCodecError> {
+        value.serialize(self)
+    }
+    unsupported!{bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str bytes none some
+        unit unit_struct unit_variant newtype_variant tuple_variant map struct_variant}
+}
+
+impl<'a> PacketContents<'a> {
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), OscCodecError> {
+        match self.state {
+            State::Empty => {
+                self.state = match value.serialize(HeadSerializer{ output: self.output })? {
+                    Head::Message => State::Message(MessageBuf::new()),
+                    Head::Bundle => State::Bundle,
+                };
+                Ok(())
+            },
+            State::Message(ref mut msg) => value.serialize(ArgSerializer::new(msg, false)),
+            State::Bundle => value.serialize(BundleElementsSerializer{ output: self.output }),
+        }
+    }
+    fn end(self) -> Result<(), OscCodecError> {
+        match self.state {
+            // A packet must at least have an address or a time tag.
+            State::Empty => Err(OscCodecError::BadFormat),
+            State::Message(msg) => {
+                write_padded_str(self.output, &msg.tags);
+                self.output.extend_from_slice(&msg.args);
+                Ok(())
+            },
+            State::Bundle => Ok(()),
+        }
+    }
+}
+
+impl_serialize_seq_like!(PacketContents);
+
+impl<'a> Serializer for HeadSerializer<'a> {
+    type Ok = Head;
+    type Error = OscCodecError;
+    type SerializeSeq = TimeTagSerializer<'a>;
+    type SerializeTuple = TimeTagSerializer<'a>;
+    type SerializeTupleStruct = TimeTagSerializer<'a>;
+    type SerializeTupleVariant = Impossible<Head, OscCodecError>;
+    type SerializeMap = Impossible<Head, OscCodecError>;
+    type SerializeStruct = TimeTagSerializer<'a>;
+    type SerializeStructVariant = Impossible<Head, OscCodecError>;
+
+    fn serialize_str(self, address: &str) -> Result<Head, OscCodecError> {
+        if !address.starts_with('/') || address.contains('\0') {
+            return Err(OscCodecError::BadFormat);
+        }
+        write_padded_str(self.output, address.as_bytes());
+        Ok(Head::Message)
+    }
+    fn serialize_seq(self, _len: Option<usize>) -> Result<TimeTagSerializer<'a>, OscCodecError> {
+        Ok(TimeTagSerializer{ output: self.output, parts: [0, 0], len: 0 })
+    }
+    fn serialize_tuple(self, len: usize) -> Result<TimeTagSerializer<'a>, OscCodecError> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<TimeTagSerializer<'a>, OscCodecError> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<TimeTagSerializer<'a>, OscCodecError> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<Head, OscCodecError> {
+        value.serialize(self)
+    }
+    unsupported!{bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char bytes none some
+        unit unit_struct unit_variant newtype_variant tuple_variant map struct_variant}
+}
+
+impl<'a> TimeTagSerializer<'a> {
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), OscCodecError> {
+        let part = value.serialize(TimeTagPartSerializer)?;
+        match self.parts.get_mut(self.len) {
+            None => return Err(OscCodecError::BadFormat),
+            Some(slot) => *slot = part,
+        }
+        self.len += 1;
+        Ok(())
+    }
+    fn end(self) -> Result<Head, OscCodecError> {
+        if self.len != 2 {
+            return Err(OscCodecError::BadFormat);
+        }
+        write_padded_str(self.output, b"#bundle");
+        write_u32(self.output, self.parts[0]);
+        write_u32(self.output, self.parts[1]);
+        Ok(Head::Bundle)
+    }
+}
+
+impl<'a> SerializeSeq for TimeTagSerializer<'a> {
+    type Ok = Head;
+    type Error = OscCodecError;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), OscCodecError> {
+        TimeTagSerializer::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Head, OscCodecError> {
+        TimeTagSerializer::end(self)
+    }
+}
+
+impl<'a> SerializeTuple for TimeTagSerializer<'a> {
+    type Ok = Head;
+    type Error = OscCodecError;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), OscCodecError> {
+        TimeTagSerializer::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Head, OscCodecError> {
+        TimeTagSerializer::end(self)
+    }
+}
+
+impl<'a> SerializeTupleStruct for TimeTagSerializer<'a> {
+    type Ok = Head;
+    type Error = OscCodecError;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), OscCodecError> {
+        TimeTagSerializer::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Head, OscCodecError> {
+        TimeTagSerializer::end(self)
+    }
+}
+
+impl<'a> SerializeStruct for TimeTagSerializer<'a> {
+    type Ok = Head;
+    type Error = OscCodecError;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, _key: &'static str, value: &T) -> Result<(), OscCodecError> {
+        TimeTagSerializer::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Head, OscCodecError> {
+        TimeTagSerializer::end(self)
+    }
+}
+
+impl Serializer for TimeTagPartSerializer {
+    type Ok = u32;
+    type Error = OscCodecError;
+    type SerializeSeq = Impossible<u32, OscCodecError>;
+    type SerializeTuple = Impossible<u32, OscCodecError>;
+    type SerializeTupleStruct = Impossible<u32, OscCodecError>;
+    type SerializeTupleVariant = Impossible<u32, OscCodecError>;
+    type SerializeMap = Impossible<u32, OscCodecError>;
+    type SerializeStruct = Impossible<u32, OscCodecError>;
+    type SerializeStructVariant = Impossible<u32, OscCodecError>;
+
+    fn serialize_u32(self, value: u32) -> Result<u32, OscCodecError> {
+        Ok(value)
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<u32, OscCodecError> {
+        value.serialize(self)
+    }
+    unsupported!{bool i8 i16 i32 i64 u8 u16 u64 f32 f64 char str bytes none some
+        unit unit_struct unit_variant newtype_variant seq tuple tuple_struct tuple_variant map struct struct_variant}
+}
+
+impl MessageBuf {
+    fn new() -> Self {
+        Self{ tags: vec![b','], args: Vec::new() }
+    }
+    /// Append a blob argument.
+    fn push_blob(&mut self, data: &[u8]) -> Result<(), OscCodecError> {
+        self.tags.push(b'b');
+        let len = checked_len(data.len())?;
+        self.args.extend_from_slice(&len.to_be_bytes());
+        self.args.extend_from_slice(data);
+        let padding = (4 - data.len() % 4) % 4;
+        self.args.extend_from_slice(&[0; 3][..padding]);
+        Ok(())
+    }
+}
+
+impl<'a> ArgSerializer<'a> {
+    fn new(msg: &'a mut MessageBuf, nested: bool) -> Self {
+        Self{ msg, nested, tag: None, bytes: None }
+    }
+    /// Append an argument with the given type tag and data.
+    fn push(self, tag: u8, data: &[u8]) -> Result<(), OscCodecError> {
+        self.msg.tags.push(tag);
+        self.msg.args.extend_from_slice(data);
+        Ok(())
+    }
+}
+
+impl<'a> Serializer for ArgSerializer<'a> {
+    type Ok = ();
+    type Error = OscCodecError;
+    type SerializeSeq = ArgListSerializer<'a>;
+    type SerializeTuple = ArgListSerializer<'a>;
+    type SerializeTupleStruct = ArgListSerializer<'a>;
+    type SerializeTupleVariant = Impossible<(), OscCodecError>;
+    type SerializeMap = Impossible<(), OscCodecError>;
+    type SerializeStruct = ArgListSerializer<'a>;
+    type SerializeStructVariant = Impossible<(), OscCodecError>;
+
+    fn serialize_bool(self, value: bool) -> Result<(), OscCodecError> {
+        self.push(if value { b'T' } else { b'F' }, &[])
+    }
+    fn serialize_i32(self, value: i32) -> Result<(), OscCodecError> {
+        self.push(b'i', &value.to_be_bytes())
+    }
+    fn serialize_i64(self, value: i64) -> Result<(), OscCodecError> {
+        self.push(b'h', &value.to_be_bytes())
+    }
+    // OSC has no unsigned integers, so these are only accepted from the
+    // wrapper types which use them as their representation.
+    fn serialize_u32(self, value: u32) -> Result<(), OscCodecError> {
+        match self.tag {
+            Some(b'r') => self.push(b'r', &value.to_be_bytes()),
+            _ => Err(OscCodecError::UnsupportedType("u32")),
+        }
+    }
+    fn serialize_u64(self, value: u64) -> Result<(), OscCodecError> {
+        match self.tag {
+            Some(b't') => self.push(b't', &value.to_be_bytes()),
+            _ => Err(OscCodecError::UnsupportedType("u64")),
+        }
+    }
+    fn serialize_f32(self, value: f32) -> Result<(), OscCodecError> {
+        self.push(b'f', &value.to_be_bytes())
+    }
+    fn serialize_f64(self, value: f64) -> Result<(), OscCodecError> {
+        self.push(b'd', &value.to_be_bytes())
+    }
+    fn serialize_char(self, value: char) -> Result<(), OscCodecError> {
+        self.push(b'c', &(value as u32).to_be_bytes())
+    }
+    // OSC has no single bytes either, but an array of them is a blob.
+    fn serialize_u8(self, value: u8) -> Result<(), OscCodecError> {
+        match self.bytes {
+            Some(bytes) => {
+                bytes.push(value);
+                Ok(())
+            },
+            None => Err(OscCodecError::UnsupportedType("u8")),
+        }
+    }
+    fn serialize_str(self, value: &str) -> Result<(), OscCodecError> {
+        if value.contains('\0') {
+            return Err(OscCodecError::UnsupportedType("string containing NUL"));
+        }
+        self.msg.tags.push(if self.tag == Some(b'S') { b'S' } else { b's' });
+        write_padded_str(&mut self.msg.args, value.as_bytes());
+        Ok(())
+    }
+    fn serialize_bytes(self, value: &[u8]) -> Result<(), OscCodecError> {
+        if self.tag == Some(b'm') {
+            if value.len() != 4 {
+                return Err(OscCodecError::UnsupportedType("MIDI message which is not 4 bytes"));
+            }
+            return self.push(b'm', value);
+        }
+        self.msg.push_blob(value)
+    }
+    fn serialize_none(self) -> Result<(), OscCodecError> {
+        self.push(b'N', &[])
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), OscCodecError> {
+        value.serialize(self)
+    }
+    // A unit payload is a message without any arguments, whereas a unit
+    // argument is nil.
+    fn serialize_unit(self) -> Result<(), OscCodecError> {
+        if self.nested {
+            self.push(b'N', &[])
+        } else {
+            Ok(())
+        }
+    }
+    fn serialize_unit_struct(self, name: &'static str) -> Result<(), OscCodecError> {
+        if name == IMPULSE_NAME {
+            self.push(b'I', &[])
+        } else {
+            self.serialize_unit()
+        }
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(mut self, name: &'static str, value: &T) -> Result<(), OscCodecError> {
+        self.tag = match name {
+            MIDI_NAME => Some(b'm'),
+            COLOR_NAME => Some(b'r'),
+            SYMBOL_NAME => Some(b'S'),
+            TIME_TAG_NAME => Some(b't'),
+            _ => self.tag,
+        };
+        value.serialize(self)
+    }
+    // A sequence within the arguments is an OSC array.
+    fn serialize_seq(self, _len: Option<usize>) -> Result<ArgListSerializer<'a>, OscCodecError> {
+        let start = self.msg.tags.len();
+        if self.nested {
+            self.msg.tags.push(b'[');
+        }
+        Ok(ArgListSerializer{ msg: self.msg, array: self.nested, start, bytes: Vec::new() })
+    }
+    fn serialize_tuple(self, len: usize) -> Result<ArgListSerializer<'a>, OscCodecError> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<ArgListSerializer<'a>, OscCodecError> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<ArgListSerializer<'a>, OscCodecError> {
+        self.serialize_seq(Some(len))
+    }
+    unsupported!{i8 i16 u16 unit_variant newtype_variant tuple_variant map struct_variant}
+}
+
+impl<'a> ArgListSerializer<'a> {
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), OscCodecError> {
+        let bytes = if self.array { Some(&mut self.bytes) } else { None };
+        value.serialize(ArgSerializer{ msg: self.msg, nested: true, tag: None, bytes })
+    }
+    fn end(self) -> Result<(), OscCodecError> {
+        if !self.array {
+            return Ok(());
+        }
+        if self.bytes.is_empty() {
+            self.msg.tags.push(b']');
+            return Ok(());
+        }
+        // Bytes can't be mixed with other arguments within an array.
+        if self.msg.tags.len() != self.start + 1 {
+            return Err(OscCodecError::UnsupportedType("u8"));
+        }
+        self.msg.tags.truncate(self.start);
+        self.msg.push_blob(&self.bytes)
+    }
+}
+
+impl_serialize_seq_like!(ArgListSerializer);
+
+impl<'a> Serializer for BundleElementsSerializer<'a> {
+    type Ok = ();
+    type Error = OscCodecError;
+    type SerializeSeq = BundleElementSerializer<'a>;
+    type SerializeTuple = BundleElementSerializer<'a>;
+    type SerializeTupleStruct = BundleElementSerializer<'a>;
+    type SerializeTupleVariant = Impossible<(), OscCodecError>;
+    type SerializeMap = Impossible<(), OscCodecError>;
+    type SerializeStruct = BundleElementSerializer<'a>;
+    type SerializeStructVariant = Impossible<(), OscCodecError>;
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<BundleElementSerializer<'a>, OscCodecError> {
+        Ok(BundleElementSerializer{ output: self.output })
+    }
+    fn serialize_tuple(self, len: usize) -> Result<BundleElementSerializer<'a>, OscCodecError> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<BundleElementSerializer<'a>, OscCodecError> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<BundleElementSerializer<'a>, OscCodecError> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<(), OscCodecError> {
+        value.serialize(self)
+    }
+    unsupported!{bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str bytes none some
+        unit unit_struct unit_variant newtype_variant tuple_variant map struct_variant}
+}
+
+impl<'a> BundleElementSerializer<'a> {
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), OscCodecError> {
+        // Each element is prefixed by its size, which is only known once it
+        // has been serialized.
+        let start = self.output.len();
+        self.output.extend_from_slice(&[0; 4]);
+        value.serialize(PacketSerializer::new(self.output))?;
+        let size = checked_len(self.output.len() - start - 4)?;
+        self.output[start..start+4].copy_from_slice(&size.to_be_bytes());
+        Ok(())
+    }
+    fn end(self) -> Result<(), OscCodecError> {
+        Ok(())
+    }
+}
+
+impl_serialize_seq_like!(BundleElementSerializer);
+
+/// Write a string, followed by at least one NUL so as to pad it to a multiple of 4 bytes.
+fn write_padded_str(output: &mut Vec<u8>, string: &[u8]) {
+    output.extend_from_slice(string);
+    let padding = 4 - string.len() % 4;
+    output.extend_from_slice(&[0; 4][..padding]);
+}
+
+fn write_u32(output: &mut Vec<u8>, value: u32) {
+    output.extend_from_slice(&value.to_be_bytes());
+}
+
+/// Convert the length of a blob or bundle element to the `i32` which OSC uses to represent it.
+fn checked_len(len: usize) -> Result<i32, OscCodecError> {
+    len.try_into().map_err(|_| OscCodecError::UnsupportedType("data longer than i32::MAX bytes"))
+}
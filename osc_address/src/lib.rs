@@ -2,12 +2,21 @@
 //! messages. It is intended to be paired with [osc_address_derive] and provides
 //! a layer of abstraction over [serde_osc],
 //! but usage of either is optional and serialization/deserialization works
-//! with any serde backend.
+//! with any serde backend. The crate also provides its own OSC 1.0 binary
+//! codec, via [`encode`] and [`decode`]. OSC 1.1 argument types which have
+//! no serde equivalent are provided as payload types, e.g. [`OscMidi`].
+//! Messages whose types aren't known at compile time, e.g. in a logging proxy,
+//! can be handled as an [`OscRawMessage`] of [`OscValue`]s.
 //!
 //! The primary feature of this crate is its [`OscMessage`] trait, which provides
 //! a **type-safe** way of encoding an OSC address and its payload while
 //! serializing/deserializating as if it were a generic
 //! `(osc_address: String, msg_payload: (...))` type suitable for [serde_osc].
+//! Incoming messages whose address is an OSC pattern can be dispatched to every
+//! matching message by deserializing them as [`OscMatches`], and patterns can be
+//! tested against plain address strings via [`OscAddressPattern`]. Path arguments
+//! with a closed set of values implement [`OscPathArg`], so that patterns can be
+//! matched against each of them.
 //!
 //! The `OscMessage` trait is intended to be implemented automatically via a
 //! `#[derive(OscMessage)]` directive, by use of [osc_address_derive]. Because
@@ -19,15 +28,49 @@
 //! [osc_address_derive]: https://crates.io/crates/osc_address_derive
 //! [serde_osc]: https://crates.io/crates/serde_osc
 //! [`OscMessage`]: trait.OscMessage.html
+//! [`OscMatches`]: struct.OscMatches.html
+//! [`OscAddressPattern`]: struct.OscAddressPattern.html
+//! [`OscPathArg`]: trait.OscPathArg.html
+//! [`encode`]: fn.encode.html
+//! [`decode`]: fn.decode.html
+//! [`OscMidi`]: struct.OscMidi.html
+//! [`OscRawMessage`]: struct.OscRawMessage.html
+//! [`OscValue`]: enum.OscValue.html
 #![feature(try_from)]
 
 #[macro_use]
 extern crate serde_derive;
+#[macro_use]
 extern crate serde;
 
+mod address;
+mod arg_buffer;
+mod codec;
+mod path_arg;
+mod pattern;
+mod raw;
+mod types;
+
 use std::convert::TryInto;
+use std::fmt;
+use std::marker::PhantomData;
 use std::time::{UNIX_EPOCH, Duration, SystemTime};
 
+pub use address::{OscAddress, OscAddressError, OscAddressWriter};
+#[doc(hidden)]
+pub use address::{match_component, match_template, BorrowedAddress, DisplayWith, FORBIDDEN_CHARS};
+#[doc(hidden)]
+pub use arg_buffer::ArgBuffer;
+pub use codec::{decode, encode, OscCodecError};
+pub use pattern::{pattern_matches, OscAddressPattern, OscComponentPattern, OscPatternError};
+#[doc(hidden)]
+pub use pattern::{match_pattern_template, pattern_address};
+pub use path_arg::{OscPathArg, OscPathArgError};
+#[doc(hidden)]
+pub use path_arg::{path_arg_candidates, EnumeratedPathArg, OpaquePathArg, PathArgNames};
+pub use raw::{OscRawArgs, OscRawMessage, OscValue};
+pub use types::{OscBlob, OscChar, OscColor, OscImpulse, OscMidi, OscSymbol, OscTimeTag};
+
 /// OSC uses ntp time (epoch of 1900), and std::time uses Unix epoch (1970).
 /// This constant is used in conversion between the two formats.
 /// There are 70 years between 1900 and 1970; 17 of them are leap years.
@@ -41,18 +84,46 @@ const DELTA_1970_1900: u32 = (70*365 + 17)*86400;
 /// programmer. They primarily facilitate implementing Serde
 /// serialization/deserialization in an ergonomic fashion.
 pub trait OscMessage<'m> : serde::Serialize + serde::Deserialize<'m> {
-    /// Append the address that this message would be sent to into the given string.
-    /// This is intended to be used as a builder method called by `get_address`.
+    /// Append the components of the address that this message would be sent to
+    /// onto the given writer.
+    /// This is intended to be used as a builder method called by `write_address`.
     /// Generally, users should not directly call this function.
-    fn build_address(&self, string: &mut String);
-    /// Determine the address that this message would be sent to, as a String.
+    ///
+    /// Fails if a component (e.g. the `Display` output of a path argument) is
+    /// not valid within an OSC address, or if the underlying writer fails.
+    fn build_address<W: fmt::Write>(&self, address: &mut OscAddressWriter<W>) -> Result<(), OscAddressError>;
+    /// Write the address that this message would be sent to into any `fmt::Write`,
+    /// e.g. a reusable `String` or a fixed-size buffer, without allocating.
+    /// If this type is a struct (i.e. it represents just the payload of a message),
+    /// then this writes the root address, "/".
+    ///
+    /// Fails if a component (e.g. the `Display` output of a path argument) is
+    /// not valid within an OSC address, or if the writer fails. In that case,
+    /// the writer may hold a partially-written address.
+    fn write_address<W: fmt::Write>(&self, writer: &mut W) -> Result<(), OscAddressError> {
+        let mut address = OscAddressWriter::new(writer);
+        self.build_address(&mut address)?;
+        address.finish().map(|_| ())
+    }
+    /// Determine the address that this message would be sent to.
     /// If this type is a struct (i.e. it represents just the payload of a message),
-    /// then this method returns an empty string.
-    /// In all other cases, it will return a string beginning with "/".
-    fn get_address(&self) -> String {
-        let mut s = String::new();
-        self.build_address(&mut s);
-        s
+    /// then this method returns the root address, "/".
+    ///
+    /// Fails if a component (e.g. the `Display` output of a path argument) is
+    /// not valid within an OSC address.
+    fn get_address(&self) -> Result<OscAddress, OscAddressError> {
+        let mut address = String::with_capacity(self.address_len());
+        self.write_address(&mut address)?;
+        Ok(OscAddress::from_validated(address))
+    }
+    /// The length in bytes of the address that this message would be sent to,
+    /// so that an encoder may pre-size its buffer.
+    /// This formats each path argument but does not allocate.
+    /// If the address is invalid, the result is only an estimate.
+    fn address_len(&self) -> usize {
+        let mut counter = LenCounter(0);
+        let _ = self.write_address(&mut counter);
+        counter.0
     }
     /// Serialize the payload of this message, and not its address.
     /// In the case that the variants of this message are also enumerated OscMessages,
@@ -62,9 +133,74 @@ pub trait OscMessage<'m> : serde::Serialize + serde::Deserialize<'m> {
     /// then this method deserializes the address + data into the appropriate enum
     /// variant.
     ///
+    /// `address` is the portion of a validated [`OscAddress`](struct.OscAddress.html)
+    /// which has not yet been routed, e.g. "/42/say" when deserializing the
+    /// `OscRenderer` of "/renderer/42/say". Implementations should route by
+    /// borrowing slices of it, so that no allocation is needed before reaching
+    /// the leaf payload.
+    ///
     /// In the case that Self is a struct and represents the payload of a message
     /// (without any address), then it is expected that address is either "" or "/".
-    fn deserialize_body<D: serde::de::SeqAccess<'m>>(address: String, seq: D) -> Result<Self, D::Error>;
+    fn deserialize_body<D: serde::de::SeqAccess<'m>>(address: &str, seq: D) -> Result<Self, D::Error>;
+    /// If `seq` represents the payload of an OSC message (i.e. the argument list),
+    /// then this method deserializes every message whose address is matched by
+    /// the OSC address pattern, e.g. "/renderer/{new,del}".
+    ///
+    /// OSC 1.0 requires the receiver to dispatch a message to every address that
+    /// its pattern matches, so this may yield more than one message. Each message
+    /// receives its own copy of the arguments. Path arguments are parsed from the
+    /// raw pattern component, as they cannot be enumerated.
+    ///
+    /// A malformed pattern (e.g. one with an unclosed '[') is reported as an error.
+    fn deserialize_pattern<D: serde::de::SeqAccess<'m>>(pattern: &str, mut seq: D) -> Result<Vec<Self>, D::Error> {
+        let pattern = OscAddressPattern::new(pattern).map_err(serde::de::Error::custom)?;
+        let args: Option<ArgBuffer> = seq.next_element()?;
+        Self::match_pattern(pattern.components(), &args.unwrap_or_default())
+    }
+    /// Deserialize every message whose address is matched by the remaining
+    /// components of an OSC address pattern from the captured message arguments.
+    /// This is intended to be used as a builder method called by `deserialize_pattern`.
+    /// Generally, users should not directly call this function.
+    ///
+    /// An error is returned if the pattern matches no message.
+    fn match_pattern<E: serde::de::Error>(pattern: &[OscComponentPattern], args: &ArgBuffer) -> Result<Vec<Self>, E>;
+    /// Convert this message into a dynamically-typed message, e.g. so that
+    /// it may be inspected or forwarded alongside unknown traffic.
+    ///
+    /// Fails if the message cannot be encoded, as with [`encode`](fn.encode.html).
+    fn to_raw(&self) -> Result<OscRawMessage, OscCodecError> {
+        // Round-trip through the codec, so that each argument receives
+        // exactly the type tag it would be sent with.
+        decode(&encode(self)?)
+    }
+    /// Convert a dynamically-typed message into this type, e.g. to dispatch
+    /// a message received as an `OscRawMessage` whose address turns out to
+    /// be known.
+    ///
+    /// Fails if the address or the arguments don't match this type.
+    fn try_from_raw(raw: &'m OscRawMessage) -> Result<Self, OscCodecError> {
+        Self::deserialize(raw::RawMessageDeserializer::new(raw))
+    }
+}
+
+/// `fmt::Write` implementation which only counts the bytes written to it.
+struct LenCounter(usize);
+
+impl fmt::Write for LenCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+/// All the messages matched by a single OSC message whose address is a pattern.
+///
+/// Deserializing an `OscMatches<M>` in place of `M` dispatches e.g. a message
+/// sent to "/renderer/{new,del}" to both "/renderer/new" and "/renderer/del".
+/// See [`OscMessage::deserialize_pattern`](trait.OscMessage.html#method.deserialize_pattern).
+#[derive(Debug)]
+pub struct OscMatches<M> {
+    messages: Vec<M>,
 }
 
 /// An OSC bundle consists of 0 or more OSC packets that are to be handled
@@ -96,7 +232,7 @@ pub enum OscTime {
     At(AbsOscTime),
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 /// OSC uses ntp time, i.e. absolute # of seconds since 1970 + a fraction of a second.
 pub struct AbsOscTime {
     sec: u32,
@@ -104,6 +240,14 @@ pub struct AbsOscTime {
 }
 
 impl<M> OscBundle<M> {
+    /// Create a bundle of packets to be handled at the given time.
+    pub fn new(time_tag: OscTime, messages: Vec<OscPacket<M>>) -> Self {
+        let time_tag = match time_tag {
+            OscTime::Now => (0, 1),
+            OscTime::At(abs_time) => abs_time.sec_frac(),
+        };
+        Self{ time_tag, messages }
+    }
     /// Return the time at which this OSC bundle should be handled.
     pub fn time_tag(&self) -> OscTime {
         OscTime::new(self.time_tag.0, self.time_tag.1)
@@ -114,6 +258,43 @@ impl<M> OscBundle<M> {
     }
 }
 
+impl<M> OscMatches<M> {
+    /// Access all messages matched by the address pattern.
+    pub fn messages(&self) -> &Vec<M> {
+        &self.messages
+    }
+    /// Take ownership of all messages matched by the address pattern.
+    pub fn into_messages(self) -> Vec<M> {
+        self.messages
+    }
+}
+
+impl<'de, M: OscMessage<'de>> serde::Deserialize<'de> for OscMatches<M> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: serde::Deserializer<'de>
+    {
+        struct MatchesVisitor<M>(PhantomData<M>);
+        impl<'de, M: OscMessage<'de>> serde::de::Visitor<'de> for MatchesVisitor<M> {
+            type Value = OscMatches<M>;
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a tuple of (String, (msg_args ...))")
+            }
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where A: serde::de::SeqAccess<'de>
+            {
+                let pattern: Option<String> = seq.next_element()?;
+                let pattern = match pattern {
+                    None => Err(serde::de::Error::invalid_length(0, &"an OSC address pattern, followed by a sequence of message arguments")),
+                    Some(pattern) => Ok(pattern),
+                }?;
+                let messages = M::deserialize_pattern(&pattern, seq)?;
+                Ok(OscMatches{ messages })
+            }
+        }
+        deserializer.deserialize_seq(MatchesVisitor(PhantomData))
+    }
+}
+
 impl OscTime {
     /// Create a OSC time from seconds and a fraction of a second.
     /// In the special case that `sec == 0` and `frac == 1`, this is to be interpreted
@@ -0,0 +1,103 @@
+//! Path arguments with a closed set of values.
+
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use pattern::OscComponentPattern;
+
+/// A path argument which takes one of a closed set of values, e.g. the
+/// "osc", "filter" or "env" of "/synth/filter/cutoff".
+///
+/// Unlike other path arguments, the values of an `OscPathArg` can be
+/// enumerated, so an address pattern such as "/synth/{osc,env}/gain" or
+/// "/synth/*/gain" is routed to each of the values that it matches.
+///
+/// This is intended to be implemented for C-like enums via
+/// `#[derive(OscPathArg)]`, by use of osc_address_derive.
+pub trait OscPathArg: FromStr + fmt::Display {
+    /// The spelling of every value within an address, in declaration order.
+    const NAMES: &'static [&'static str];
+}
+
+/// Error returned when parsing an `OscPathArg` from text which isn't the name
+/// of any of its values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OscPathArgError {
+    name: String,
+}
+
+impl OscPathArgError {
+    /// Create an error for the given unrecognized name.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self{ name: name.into() }
+    }
+    /// The text which failed to parse.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Display for OscPathArgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown OSC path argument \"{}\"", self.name)
+    }
+}
+
+impl Error for OscPathArgError {}
+
+/// Looks up the names of a path argument's values, if it implements
+/// `OscPathArg`, without requiring that it does. This is used by
+/// `#[derive(OscMessage)]`, which can't tell whether a type implements it.
+///
+/// Call `(&&PathArgNames::<T>::new()).names()` with both `EnumeratedPathArg`
+/// and `OpaquePathArg` in scope: method resolution picks the former wherever
+/// `T: OscPathArg` is known to hold, and falls back to the latter otherwise.
+#[doc(hidden)]
+pub struct PathArgNames<T>(PhantomData<T>);
+
+impl<T> PathArgNames<T> {
+    pub fn new() -> Self {
+        PathArgNames(PhantomData)
+    }
+}
+
+impl<T> Default for PathArgNames<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[doc(hidden)]
+pub trait EnumeratedPathArg {
+    fn names(&self) -> Option<&'static [&'static str]>;
+}
+
+impl<T: OscPathArg> EnumeratedPathArg for &PathArgNames<T> {
+    fn names(&self) -> Option<&'static [&'static str]> {
+        Some(T::NAMES)
+    }
+}
+
+#[doc(hidden)]
+pub trait OpaquePathArg {
+    fn names(&self) -> Option<&'static [&'static str]>;
+}
+
+impl<T> OpaquePathArg for PathArgNames<T> {
+    fn names(&self) -> Option<&'static [&'static str]> {
+        None
+    }
+}
+
+/// Return the text to parse a path argument from, for each value it may take
+/// when matching a component of an address pattern: the names matched by the
+/// pattern if the values can be enumerated, or else the raw pattern component.
+#[doc(hidden)]
+pub fn path_arg_candidates<'a>(pattern: &'a OscComponentPattern, names: Option<&'static [&'static str]>) -> Vec<&'a str> {
+    match names {
+        Some(names) => names.iter().cloned().filter(|name| pattern.matches(name)).collect(),
+        None => vec![pattern.as_str()],
+    }
+}
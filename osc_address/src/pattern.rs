@@ -0,0 +1,355 @@
+//! Matching of OSC 1.0 address patterns against concrete OSC addresses.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use address::{match_component, FORBIDDEN_CHARS};
+
+/// An OSC address pattern, e.g. "/renderer/*/{new,del}", parsed once so that
+/// it may be efficiently tested against any number of addresses.
+///
+/// The pattern syntax is that of OSC 1.0. Within each component (i.e. the
+/// text between two adjacent '/'):
+///
+/// - `?` matches any single character.
+/// - `*` matches any sequence of zero or more characters.
+/// - `[abc]` matches any character in the set. Sets may contain ranges like
+///   `[a-z]`, and are negated by a leading `!`, as in `[!0-9]`.
+/// - `{foo,bar}` matches any of the comma-separated strings.
+/// - Any other character matches only itself.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscAddressPattern {
+    source: String,
+    components: Vec<OscComponentPattern>,
+}
+
+/// A single component of an [`OscAddressPattern`](struct.OscAddressPattern.html),
+/// i.e. the portion of the pattern between two adjacent '/'.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscComponentPattern {
+    source: String,
+    tokens: Vec<Token>,
+}
+
+/// Syntax error encountered while parsing an OSC address pattern.
+/// Offsets are byte offsets into the full pattern.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OscPatternError {
+    /// The pattern does not begin with '/'.
+    MissingLeadingSlash,
+    /// The '[' or '{' at the given offset is not closed within its component.
+    Unclosed(char, usize),
+    /// The character at the given offset is not allowed there, e.g. a ']'
+    /// without a matching '[', or a '{' inside of a "{...}".
+    Unexpected(char, usize),
+    /// The component which begins at the given offset is empty, as in "/a//b".
+    EmptyComponent(usize),
+    /// The character at the given offset is never allowed outside of a "[...]"
+    /// or "{...}", e.g. a space or '#'.
+    ForbiddenChar(char, usize),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// Text that must be matched exactly.
+    Literal(String),
+    /// `?`
+    AnyChar,
+    /// `*`
+    AnyRun,
+    /// `[...]`, as inclusive character ranges. A lone character `c` is the range `(c, c)`.
+    /// The set is negated if the flag is set.
+    Set(bool, Vec<(char, char)>),
+    /// `{...}`
+    Options(Vec<String>),
+}
+
+/// Test whether a single component of an OSC address is matched by the
+/// corresponding component of an OSC address pattern. A malformed pattern
+/// matches nothing.
+///
+/// This parses `pattern` on each call; to test the same pattern against many
+/// addresses, parse it once as an [`OscAddressPattern`](struct.OscAddressPattern.html).
+pub fn pattern_matches(pattern: &str, component: &str) -> bool {
+    OscComponentPattern::new(pattern).map(|pattern| pattern.matches(component)).unwrap_or(false)
+}
+
+/// Match the leading components of an address pattern against a template
+/// such as "grid/{}/{}/press"; see `osc_address::match_template`.
+///
+/// Literal components of the template are matched against the pattern as usual,
+/// while the placeholders are taken from the raw text of the pattern components.
+/// The unmatched remainder of the pattern is returned. This is used by
+/// `#[derive(OscMessage)]`.
+#[doc(hidden)]
+pub fn match_pattern_template<'a>(pattern: &'a [OscComponentPattern], template: &[&[&str]], fields: &mut [&'a str]) -> Option<&'a [OscComponentPattern]> {
+    if pattern.len() < template.len() {
+        return None;
+    }
+    let mut field_idx = 0;
+    for (component, literals) in pattern.iter().zip(template) {
+        let num_fields = literals.len().saturating_sub(1);
+        let component_fields = fields.get_mut(field_idx..field_idx + num_fields)?;
+        let matched = match *literals {
+            [literal] => component.matches(literal),
+            _ => match_component(component.as_str(), literals, component_fields),
+        };
+        if !matched {
+            return None;
+        }
+        field_idx += num_fields;
+    }
+    Some(&pattern[template.len()..])
+}
+
+/// Rebuild the text of the components of an address pattern, e.g. "/foo/*",
+/// or "/" if there are none. This is used by `#[derive(OscMessage)]` and by
+/// `OscRawMessage` to capture the unmatched remainder of a pattern.
+#[doc(hidden)]
+pub fn pattern_address(pattern: &[OscComponentPattern]) -> String {
+    if pattern.is_empty() {
+        return "/".to_owned();
+    }
+    let mut address = String::new();
+    for component in pattern {
+        address.push('/');
+        address.push_str(component.as_str());
+    }
+    address
+}
+
+impl OscAddressPattern {
+    /// Parse an OSC address pattern. It must begin with '/'.
+    pub fn new(pattern: &str) -> Result<Self, OscPatternError> {
+        if !pattern.starts_with('/') {
+            return Err(OscPatternError::MissingLeadingSlash);
+        }
+        // The root address "/" has no components.
+        let components = if pattern == "/" {
+            Vec::new()
+        } else {
+            let mut offset = 1;
+            let mut components = Vec::new();
+            for component in pattern[1..].split('/') {
+                if component.is_empty() {
+                    return Err(OscPatternError::EmptyComponent(offset));
+                }
+                components.push(OscComponentPattern::parse(component, offset)?);
+                offset += component.len() + 1;
+            }
+            components
+        };
+        Ok(Self{ source: pattern.to_owned(), components })
+    }
+    /// The pattern, as it was originally written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+    /// Access each component of the pattern, in order.
+    pub fn components(&self) -> &[OscComponentPattern] {
+        &self.components
+    }
+    /// Test whether the OSC address (e.g. "/renderer/42/new") is matched by
+    /// this pattern. Every component of the address must be matched by the
+    /// corresponding component of the pattern.
+    pub fn matches(&self, address: &str) -> bool {
+        if !address.starts_with('/') {
+            return false;
+        }
+        if address == "/" {
+            return self.components.is_empty();
+        }
+        let mut address_components = address[1..].split('/');
+        self.components.iter().all(|pattern| match address_components.next() {
+            Some(component) => pattern.matches(component),
+            None => false,
+        }) && address_components.next().is_none()
+    }
+}
+
+impl OscComponentPattern {
+    /// Parse a single component of an OSC address pattern, e.g. "{new,del}".
+    /// The component may not contain '/'.
+    pub fn new(pattern: &str) -> Result<Self, OscPatternError> {
+        if let Some(idx) = pattern.find('/') {
+            return Err(OscPatternError::Unexpected('/', idx));
+        }
+        Self::parse(pattern, 0)
+    }
+    /// The component pattern, as it was originally written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+    /// Whether this component contains no wildcards, in which case it matches
+    /// only the text returned by `as_str`.
+    pub fn is_literal(&self) -> bool {
+        self.tokens.iter().all(|token| matches!(*token, Token::Literal(_)))
+    }
+    /// Test whether a single component of an OSC address (e.g. "new") is
+    /// matched by this component pattern.
+    pub fn matches(&self, component: &str) -> bool {
+        match_tokens(&self.tokens, component)
+    }
+    /// Parse a component which begins at byte `offset` of the full pattern.
+    fn parse(source: &str, offset: usize) -> Result<Self, OscPatternError> {
+        let mut tokens = Vec::new();
+        let mut rest = source;
+        while let Some(c) = rest.chars().next() {
+            let pos = offset + source.len() - rest.len();
+            match c {
+                '[' | '{' => {
+                    let close = if c == '[' { ']' } else { '}' };
+                    let close_idx = match rest.find(close) {
+                        None => return Err(OscPatternError::Unclosed(c, pos)),
+                        Some(idx) => idx,
+                    };
+                    let interior = &rest[1..close_idx];
+                    if let Some(idx) = interior.find(|c| "[]{}".contains(c)) {
+                        return Err(OscPatternError::Unexpected(interior[idx..].chars().next().unwrap(), pos + 1 + idx));
+                    }
+                    tokens.push(if c == '[' {
+                        parse_set(interior)
+                    } else {
+                        Token::Options(interior.split(',').map(str::to_owned).collect())
+                    });
+                    rest = &rest[close_idx+1..];
+                },
+                ']' | '}' => return Err(OscPatternError::Unexpected(c, pos)),
+                '?' => {
+                    tokens.push(Token::AnyChar);
+                    rest = &rest[1..];
+                },
+                '*' => {
+                    // Consecutive '*' are equivalent to a single one.
+                    if tokens.last() != Some(&Token::AnyRun) {
+                        tokens.push(Token::AnyRun);
+                    }
+                    rest = &rest[1..];
+                },
+                _ => {
+                    let end_idx = rest.find(|c| "[]{}?*".contains(c)).unwrap_or(rest.len());
+                    if let Some((idx, c)) = rest[..end_idx].char_indices().find(|&(_, c)| FORBIDDEN_CHARS.contains(c)) {
+                        return Err(OscPatternError::ForbiddenChar(c, pos + idx));
+                    }
+                    tokens.push(Token::Literal(rest[..end_idx].to_owned()));
+                    rest = &rest[end_idx..];
+                },
+            }
+        }
+        Ok(Self{ source: source.to_owned(), tokens })
+    }
+}
+
+impl FromStr for OscAddressPattern {
+    type Err = OscPatternError;
+    fn from_str(s: &str) -> Result<Self, OscPatternError> {
+        Self::new(s)
+    }
+}
+
+impl fmt::Display for OscAddressPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl fmt::Display for OscComponentPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl fmt::Display for OscPatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            OscPatternError::MissingLeadingSlash => write!(f, "OSC address pattern must begin with '/'"),
+            OscPatternError::Unclosed(c, pos) => write!(f, "unclosed '{}' at offset {} of OSC address pattern", c, pos),
+            OscPatternError::Unexpected(c, pos) => write!(f, "unexpected '{}' at offset {} of OSC address pattern", c, pos),
+            OscPatternError::EmptyComponent(pos) => write!(f, "empty component at offset {} of OSC address pattern", pos),
+            OscPatternError::ForbiddenChar(c, pos) => write!(f, "forbidden character '{}' at offset {} of OSC address pattern", c, pos),
+        }
+    }
+}
+
+impl Error for OscPatternError {}
+
+/// Parse the interior of a `[...]` pattern, e.g. "a-z" or "!0-9".
+fn parse_set(set: &str) -> Token {
+    let (negate, set) = match set.strip_prefix('!') {
+        Some(set) => (true, set),
+        None => (false, set),
+    };
+    let mut ranges = Vec::new();
+    let mut chars = set.chars();
+    while let Some(low) = chars.next() {
+        // "a-z" denotes a range, whereas a '-' at the end of the set is literal.
+        let mut lookahead = chars.clone();
+        match (lookahead.next(), lookahead.next()) {
+            (Some('-'), Some(high)) => {
+                chars = lookahead;
+                ranges.push((low, high));
+            },
+            _ => ranges.push((low, low)),
+        }
+    }
+    Token::Set(negate, ranges)
+}
+
+/// Test whether the sequence of tokens matches the entirety of `component`.
+///
+/// Rather than backtracking, which takes exponential time in the number of '*'
+/// for a pattern such as "*a*a*a*b", this tracks every byte offset into
+/// `component` at which the tokens matched so far may end, one token at a time.
+/// This bounds the work by the number of tokens times the length of the component.
+fn match_tokens(tokens: &[Token], component: &str) -> bool {
+    // reachable[idx] is whether the tokens so far can match exactly component[..idx].
+    let mut reachable = vec![false; component.len() + 1];
+    reachable[0] = true;
+    // The offsets reachable after the current token; swapped with `reachable` after each one.
+    let mut next = vec![false; component.len() + 1];
+    for token in tokens {
+        for reached in next.iter_mut() {
+            *reached = false;
+        }
+        let starts = reachable.iter().enumerate().filter(|&(_, &reached)| reached).map(|(idx, _)| idx);
+        match *token {
+            Token::Literal(ref text) => for idx in starts {
+                if component[idx..].starts_with(text.as_str()) {
+                    next[idx + text.len()] = true;
+                }
+            },
+            Token::AnyChar => for idx in starts {
+                if let Some(c) = component[idx..].chars().next() {
+                    next[idx + c.len_utf8()] = true;
+                }
+            },
+            Token::AnyRun => {
+                // '*' reaches every character boundary from the earliest start onward.
+                if let Some(first) = starts.min() {
+                    for (idx, _) in component[first..].char_indices() {
+                        next[first + idx] = true;
+                    }
+                    next[component.len()] = true;
+                }
+            },
+            Token::Set(negate, ref ranges) => for idx in starts {
+                if let Some(c) = component[idx..].chars().next() {
+                    let in_set = ranges.iter().any(|&(low, high)| low <= c && c <= high);
+                    if in_set != negate {
+                        next[idx + c.len_utf8()] = true;
+                    }
+                }
+            },
+            Token::Options(ref options) => for idx in starts {
+                for option in options {
+                    if component[idx..].starts_with(option.as_str()) {
+                        next[idx + option.len()] = true;
+                    }
+                }
+            },
+        }
+        ::std::mem::swap(&mut reachable, &mut next);
+    }
+    reachable[component.len()]
+}
@@ -0,0 +1,615 @@
+//! Dynamically-typed OSC messages, for handling traffic whose types are not
+//! known at compile time.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::slice;
+use serde;
+use serde::de::{self, DeserializeSeed, Deserializer, EnumAccess, IntoDeserializer, SeqAccess, Unexpected, VariantAccess, Visitor};
+use serde::de::value::SeqDeserializer;
+use serde::ser::{SerializeTuple, Serializer};
+
+use codec::args::{deserialize_args, deserialize_args_unit, trailing_arguments, ArgList};
+use {pattern_address, ArgBuffer, OscAddressError, OscAddressWriter, OscComponentPattern, OscMessage};
+use types::{OscBlob, OscColor, OscImpulse, OscMidi, OscTimeTag, SYMBOL_NAME, VALUE_NAME};
+
+/// A single OSC argument of any type, e.g. as received from a peer whose
+/// messages are not described by any `OscMessage` type.
+///
+/// When decoded by [`decode`](fn.decode.html), the exact type tag of each
+/// argument is preserved, so that e.g. a symbol ('S') is not confused with a
+/// string ('s'). Other serde backends (and untagged types such as
+/// [`OscPacket`](enum.OscPacket.html)) report only the serde type of each
+/// argument, in which case the closest OSC type is chosen.
+///
+/// Arrays may be nested up to four levels deep.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OscValue {
+    /// 'i'
+    Int(i32),
+    /// 'f'
+    Float(f32),
+    /// 's'
+    String(String),
+    /// 'b'
+    Blob(Vec<u8>),
+    /// 'T' or 'F'
+    Bool(bool),
+    /// 'N'
+    Nil,
+    /// 'I'
+    Impulse,
+    /// 'h'
+    Int64(i64),
+    /// 'd'
+    Double(f64),
+    /// 'c'
+    Char(char),
+    /// 'r'
+    Color(OscColor),
+    /// 'm'
+    Midi(OscMidi),
+    /// 't'
+    TimeTag(OscTimeTag),
+    /// 'S'
+    Symbol(String),
+    /// An array of arguments, delimited by '[' and ']'.
+    Array(Vec<OscValue>),
+}
+
+/// An OSC message of any address and arguments.
+///
+/// This implements [`OscMessage`](trait.OscMessage.html), so it may be
+/// encoded, decoded, or pattern-matched in place of a derived message type.
+/// A derived message can be converted to and from it via
+/// [`OscMessage::to_raw`](trait.OscMessage.html#method.to_raw) and
+/// [`OscMessage::try_from_raw`](trait.OscMessage.html#method.try_from_raw).
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscRawMessage {
+    /// The address of the message, e.g. "/renderer/42/say".
+    ///
+    /// A message received with an address pattern keeps the pattern here.
+    /// Serializing the message writes the address unvalidated, so that e.g.
+    /// a proxy may forward such a message unchanged.
+    pub address: String,
+    /// The arguments of the message.
+    pub args: Vec<OscValue>,
+}
+
+/// The arguments of a message, of any types.
+///
+/// This is the payload of a `#[osc_address(fallback)]` variant, which
+/// captures the messages that match no other variant of a derived enum.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OscRawArgs(pub Vec<OscValue>);
+
+impl OscValue {
+    /// The OSC type tag of this value. For an array, this is '['.
+    pub fn type_tag(&self) -> char {
+        match *self {
+            OscValue::Int(_) => 'i',
+            OscValue::Float(_) => 'f',
+            OscValue::String(_) => 's',
+            OscValue::Blob(_) => 'b',
+            OscValue::Bool(true) => 'T',
+            OscValue::Bool(false) => 'F',
+            OscValue::Nil => 'N',
+            OscValue::Impulse => 'I',
+            OscValue::Int64(_) => 'h',
+            OscValue::Double(_) => 'd',
+            OscValue::Char(_) => 'c',
+            OscValue::Color(_) => 'r',
+            OscValue::Midi(_) => 'm',
+            OscValue::TimeTag(_) => 't',
+            OscValue::Symbol(_) => 'S',
+            OscValue::Array(_) => '[',
+        }
+    }
+}
+
+impl OscRawMessage {
+    /// Create a message from its address and arguments.
+    pub fn new<S: Into<String>>(address: S, args: Vec<OscValue>) -> Self {
+        Self{ address: address.into(), args }
+    }
+}
+
+impl serde::Serialize for OscValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            OscValue::Int(v) => serializer.serialize_i32(v),
+            OscValue::Float(v) => serializer.serialize_f32(v),
+            OscValue::String(ref v) => serializer.serialize_str(v),
+            OscValue::Blob(ref v) => serializer.serialize_bytes(v),
+            OscValue::Bool(v) => serializer.serialize_bool(v),
+            OscValue::Nil => serializer.serialize_none(),
+            OscValue::Impulse => OscImpulse.serialize(serializer),
+            OscValue::Int64(v) => serializer.serialize_i64(v),
+            OscValue::Double(v) => serializer.serialize_f64(v),
+            OscValue::Char(v) => serializer.serialize_char(v),
+            OscValue::Color(ref v) => v.serialize(serializer),
+            OscValue::Midi(ref v) => v.serialize(serializer),
+            OscValue::TimeTag(ref v) => v.serialize(serializer),
+            OscValue::Symbol(ref v) => serializer.serialize_newtype_struct(SYMBOL_NAME, v),
+            OscValue::Array(ref v) => v.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for OscValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ValueSeed::<Depth0>(PhantomData).deserialize(deserializer)
+    }
+}
+
+/// Deserializes an `OscValue`. `N` bounds the depth of the arrays which may
+/// be nested within it.
+struct ValueSeed<N>(PhantomData<N>);
+
+/// Deserializing an array is generic over the deserializer of its elements,
+/// so unbounded nesting would instantiate infinitely many types for
+/// deserializers which nest a new type per level, like serde_osc's. Instead,
+/// each level of nesting is a distinct type, up to a fixed limit.
+trait ArrayDepth {
+    /// Deserialize the elements of an array.
+    fn visit_array<'de, A: SeqAccess<'de>>(seq: A) -> Result<Vec<OscValue>, A::Error>;
+}
+
+struct Depth0;
+struct Depth1;
+struct Depth2;
+struct Depth3;
+struct TooDeep;
+
+macro_rules! impl_array_depth {
+    ($($depth:ident => $inner:ident),*) => {
+        $(
+            impl ArrayDepth for $depth {
+                fn visit_array<'de, A: SeqAccess<'de>>(mut seq: A) -> Result<Vec<OscValue>, A::Error> {
+                    let mut values = Vec::new();
+                    while let Some(value) = seq.next_element_seed(ValueSeed::<$inner>(PhantomData))? {
+                        values.push(value);
+                    }
+                    Ok(values)
+                }
+            }
+        )*
+    };
+}
+
+impl_array_depth!(Depth0 => Depth1, Depth1 => Depth2, Depth2 => Depth3, Depth3 => TooDeep);
+
+impl ArrayDepth for TooDeep {
+    fn visit_array<'de, A: SeqAccess<'de>>(_seq: A) -> Result<Vec<OscValue>, A::Error> {
+        Err(de::Error::custom("OSC arrays are nested too deeply"))
+    }
+}
+
+impl<'de, N: ArrayDepth> DeserializeSeed<'de> for ValueSeed<N> {
+    type Value = OscValue;
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<OscValue, D::Error> {
+        // Ask for the exact type tag; deserializers which don't know it treat
+        // this like `deserialize_any`.
+        deserializer.deserialize_newtype_struct(VALUE_NAME, self)
+    }
+}
+
+impl<'de, N: ArrayDepth> Visitor<'de> for ValueSeed<N> {
+    type Value = OscValue;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an OSC argument")
+    }
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<OscValue, E> {
+        Ok(OscValue::Bool(v))
+    }
+    fn visit_i32<E: de::Error>(self, v: i32) -> Result<OscValue, E> {
+        Ok(OscValue::Int(v))
+    }
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<OscValue, E> {
+        Ok(OscValue::Int64(v))
+    }
+    fn visit_u32<E: de::Error>(self, v: u32) -> Result<OscValue, E> {
+        Ok(OscValue::Color(OscColor::from_rgba(v)))
+    }
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<OscValue, E> {
+        Ok(OscValue::TimeTag(OscTimeTag::from_bits(v)))
+    }
+    fn visit_f32<E: de::Error>(self, v: f32) -> Result<OscValue, E> {
+        Ok(OscValue::Float(v))
+    }
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<OscValue, E> {
+        Ok(OscValue::Double(v))
+    }
+    fn visit_char<E: de::Error>(self, v: char) -> Result<OscValue, E> {
+        Ok(OscValue::Char(v))
+    }
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<OscValue, E> {
+        Ok(OscValue::String(v.to_owned()))
+    }
+    fn visit_string<E: de::Error>(self, v: String) -> Result<OscValue, E> {
+        Ok(OscValue::String(v))
+    }
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<OscValue, E> {
+        Ok(OscValue::Blob(v.to_owned()))
+    }
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<OscValue, E> {
+        Ok(OscValue::Blob(v))
+    }
+    fn visit_none<E: de::Error>(self) -> Result<OscValue, E> {
+        Ok(OscValue::Nil)
+    }
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<OscValue, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+    fn visit_unit<E: de::Error>(self) -> Result<OscValue, E> {
+        Ok(OscValue::Impulse)
+    }
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<OscValue, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<OscValue, A::Error> {
+        N::visit_array(seq).map(OscValue::Array)
+    }
+    /// Visit an argument whose type tag is known, as a variant named by it.
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<OscValue, A::Error> {
+        let (tag, variant): (char, A::Variant) = data.variant()?;
+        Ok(match tag {
+            'i' => OscValue::Int(variant.newtype_variant()?),
+            'f' => OscValue::Float(variant.newtype_variant()?),
+            's' => OscValue::String(variant.newtype_variant()?),
+            'b' => OscValue::Blob(variant.newtype_variant::<OscBlob>()?.0),
+            'T' | 'F' => {
+                variant.unit_variant()?;
+                OscValue::Bool(tag == 'T')
+            },
+            'N' => {
+                variant.unit_variant()?;
+                OscValue::Nil
+            },
+            'I' => {
+                variant.unit_variant()?;
+                OscValue::Impulse
+            },
+            'h' => OscValue::Int64(variant.newtype_variant()?),
+            'd' => OscValue::Double(variant.newtype_variant()?),
+            'c' => OscValue::Char(variant.newtype_variant()?),
+            'r' => OscValue::Color(variant.newtype_variant()?),
+            'm' => OscValue::Midi(variant.newtype_variant()?),
+            't' => OscValue::TimeTag(variant.newtype_variant()?),
+            'S' => OscValue::Symbol(variant.newtype_variant()?),
+            _ => return Err(de::Error::invalid_value(Unexpected::Char(tag), &"an OSC type tag")),
+        })
+    }
+}
+
+/// Deserializer that replays a sequence of values, i.e. an argument list or
+/// an array within it.
+pub(crate) struct ValuesDeserializer<'a, E> {
+    values: slice::Iter<'a, OscValue>,
+    marker: PhantomData<E>,
+}
+
+/// Deserializer that replays a single value.
+struct ValueDeserializer<'a, E> {
+    value: &'a OscValue,
+    marker: PhantomData<E>,
+}
+
+/// Presents a value as an enum variant named by its type tag, for
+/// `ValueSeed::visit_enum`.
+struct TaggedValue<'a, E> {
+    value: &'a OscValue,
+    marker: PhantomData<E>,
+}
+
+impl<'a, E> ValuesDeserializer<'a, E> {
+    pub(crate) fn new(values: &'a [OscValue]) -> Self {
+        Self{ values: values.iter(), marker: PhantomData }
+    }
+}
+
+impl<'de, E: de::Error> ArgList<'de> for ValuesDeserializer<'de, E> {
+    fn remaining(&self) -> usize {
+        self.values.len()
+    }
+    fn finish(&self) -> Result<(), E> {
+        match self.values.len() {
+            0 => Ok(()),
+            remaining => Err(trailing_arguments(remaining)),
+        }
+    }
+}
+
+impl<'de, E: de::Error> Deserializer<'de> for ValuesDeserializer<'de, E> {
+    type Error = E;
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        deserialize_args(self, visitor)
+    }
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        deserialize_args_unit(self, visitor)
+    }
+    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        self.deserialize_unit(visitor)
+    }
+    forward_to_deserialize_any! {
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string option
+        seq bytes byte_buf map newtype_struct
+        tuple_struct struct identifier tuple enum ignored_any
+    }
+}
+
+impl<'de, E: de::Error> SeqAccess<'de> for ValuesDeserializer<'de, E> {
+    type Error = E;
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, E>
+        where T: DeserializeSeed<'de>
+    {
+        match self.values.next() {
+            None => Ok(None),
+            Some(value) => seed.deserialize(ValueDeserializer{ value, marker: PhantomData }).map(Some),
+        }
+    }
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+impl<'de, E: de::Error> Deserializer<'de> for ValueDeserializer<'de, E> {
+    type Error = E;
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        // Mirror the codec, so that replayed values deserialize alike.
+        match *self.value {
+            OscValue::Int(v) => visitor.visit_i32(v),
+            OscValue::Float(v) => visitor.visit_f32(v),
+            OscValue::String(ref v) => visitor.visit_borrowed_str(v),
+            OscValue::Blob(ref v) => visitor.visit_borrowed_bytes(v),
+            OscValue::Bool(v) => visitor.visit_bool(v),
+            OscValue::Nil => visitor.visit_none(),
+            OscValue::Impulse => visitor.visit_unit(),
+            OscValue::Int64(v) => visitor.visit_i64(v),
+            OscValue::Double(v) => visitor.visit_f64(v),
+            OscValue::Char(v) => visitor.visit_char(v),
+            OscValue::Color(v) => visitor.visit_u32(v.to_rgba()),
+            OscValue::Midi(v) => visitor.visit_bytes(&v.to_bytes()),
+            OscValue::TimeTag(v) => visitor.visit_u64(v.to_bits()),
+            OscValue::Symbol(ref v) => visitor.visit_borrowed_str(v),
+            OscValue::Array(ref v) => ValuesDeserializer::new(v).deserialize_any(visitor),
+        }
+    }
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        match *self.value {
+            OscValue::Nil => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        match *self.value {
+            OscValue::Nil => visitor.visit_unit(),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+    // As in the codec, a blob is a sequence of bytes, and an empty array may be an empty blob.
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        match *self.value {
+            OscValue::Blob(ref v) => SeqDeserializer::new(v.iter().cloned()).deserialize_any(visitor),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        self.deserialize_seq(visitor)
+    }
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        match *self.value {
+            OscValue::Array(ref v) if v.is_empty() => visitor.visit_borrowed_bytes(&[]),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        self.deserialize_bytes(visitor)
+    }
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        match *self.value {
+            OscValue::Array(_) => self.deserialize_any(visitor),
+            _ if name == VALUE_NAME => visitor.visit_enum(TaggedValue{ value: self.value, marker: PhantomData }),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+    forward_to_deserialize_any! {
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string
+        map unit_struct
+        tuple_struct struct identifier enum ignored_any
+    }
+}
+
+impl<'de, E: de::Error> EnumAccess<'de> for TaggedValue<'de, E> {
+    type Error = E;
+    type Variant = Self;
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), E>
+        where V: DeserializeSeed<'de>
+    {
+        let tag = seed.deserialize(self.value.type_tag().into_deserializer())?;
+        Ok((tag, self))
+    }
+}
+
+impl<'de, E: de::Error> VariantAccess<'de> for TaggedValue<'de, E> {
+    type Error = E;
+    fn unit_variant(self) -> Result<(), E> {
+        Ok(())
+    }
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, E>
+        where T: DeserializeSeed<'de>
+    {
+        seed.deserialize(ValueDeserializer{ value: self.value, marker: PhantomData })
+    }
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        Err(de::Error::invalid_type(Unexpected::NewtypeVariant, &"a tuple variant"))
+    }
+    fn struct_variant<V>(self, _fields: &'static [&'static str], _visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        Err(de::Error::invalid_type(Unexpected::NewtypeVariant, &"a struct variant"))
+    }
+}
+
+impl From<Vec<OscValue>> for OscRawArgs {
+    fn from(args: Vec<OscValue>) -> Self {
+        OscRawArgs(args)
+    }
+}
+
+impl serde::Serialize for OscRawArgs {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for OscRawArgs {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RawArgsVisitor;
+        impl<'de> Visitor<'de> for RawArgsVisitor {
+            type Value = OscRawArgs;
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a sequence of message arguments")
+            }
+            // Like serde_osc, a message without arguments may be a unit.
+            fn visit_unit<E: de::Error>(self) -> Result<OscRawArgs, E> {
+                Ok(OscRawArgs::default())
+            }
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<OscRawArgs, A::Error> {
+                let mut args = Vec::new();
+                while let Some(arg) = seq.next_element()? {
+                    args.push(arg);
+                }
+                Ok(OscRawArgs(args))
+            }
+        }
+        deserializer.deserialize_seq(RawArgsVisitor)
+    }
+}
+
+impl<'de> OscMessage<'de> for OscRawMessage {
+    fn build_address<W: fmt::Write>(&self, address: &mut OscAddressWriter<W>) -> Result<(), OscAddressError> {
+        address.push_address(&self.address)
+    }
+    fn serialize_body<S: SerializeTuple>(&self, serializer: &mut S) -> Result<(), S::Error> {
+        serializer.serialize_element(&self.args)
+    }
+    fn deserialize_body<D: SeqAccess<'de>>(address: &str, mut seq: D) -> Result<Self, D::Error> {
+        let args: Option<Vec<OscValue>> = seq.next_element()?;
+        Ok(OscRawMessage::new(address, args.unwrap_or_default()))
+    }
+    /// A pattern matches exactly one raw message, which keeps the pattern as
+    /// its address.
+    fn match_pattern<E: de::Error>(pattern: &[OscComponentPattern], args: &ArgBuffer) -> Result<Vec<Self>, E> {
+        Ok(vec![OscRawMessage::new(pattern_address(pattern), args.values().to_vec())])
+    }
+}
+
+impl serde::Serialize for OscRawMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Unlike a derived message, the address is not validated, so that
+        // address patterns may be forwarded.
+        let mut tup = serializer.serialize_tuple(2)?;
+        tup.serialize_element(&self.address)?;
+        self.serialize_body(&mut tup)?;
+        tup.end()
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for OscRawMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RawMessageVisitor;
+        impl<'de> Visitor<'de> for RawMessageVisitor {
+            type Value = OscRawMessage;
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a tuple of (String, (msg_args ...))")
+            }
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<OscRawMessage, A::Error> {
+                let address: Option<String> = seq.next_element()?;
+                let address = match address {
+                    None => Err(de::Error::invalid_length(0, &"an OSC address string, followed by a sequence of message arguments")),
+                    Some(address) => Ok(address),
+                }?;
+                OscRawMessage::deserialize_body(&address, seq)
+            }
+        }
+        deserializer.deserialize_seq(RawMessageVisitor)
+    }
+}
+
+/// Deserializes a raw message as if it were a decoded packet, for
+/// `OscMessage::try_from_raw`.
+pub(crate) struct RawMessageDeserializer<'a, E> {
+    message: &'a OscRawMessage,
+    marker: PhantomData<E>,
+}
+
+/// Visits the address and the arguments of a raw message.
+struct RawMessageAccess<'a, E> {
+    address: Option<&'a str>,
+    args: Option<&'a [OscValue]>,
+    marker: PhantomData<E>,
+}
+
+impl<'a, E> RawMessageDeserializer<'a, E> {
+    pub(crate) fn new(message: &'a OscRawMessage) -> Self {
+        Self{ message, marker: PhantomData }
+    }
+}
+
+impl<'de, E: de::Error> Deserializer<'de> for RawMessageDeserializer<'de, E> {
+    type Error = E;
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, E>
+        where V: Visitor<'de>
+    {
+        visitor.visit_seq(RawMessageAccess{
+            address: Some(&self.message.address),
+            args: Some(&self.message.args),
+            marker: PhantomData,
+        })
+    }
+    forward_to_deserialize_any! {
+        bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string unit option
+        seq bytes byte_buf map unit_struct newtype_struct
+        tuple_struct struct identifier tuple enum ignored_any
+    }
+}
+
+impl<'de, E: de::Error> SeqAccess<'de> for RawMessageAccess<'de, E> {
+    type Error = E;
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, E>
+        where T: DeserializeSeed<'de>
+    {
+        if let Some(address) = self.address.take() {
+            return seed.deserialize(de::value::BorrowedStrDeserializer::new(address)).map(Some);
+        }
+        match self.args.take() {
+            Some(args) => seed.deserialize(ValuesDeserializer::new(args)).map(Some),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Payload types for the OSC argument types which have no direct serde equivalent.
+//!
+//! Each type serializes as a newtype struct with a reserved name, which the
+//! codec of this crate recognizes in order to choose the OSC type tag. Other
+//! serde backends see only the inner value.
+
+use std::fmt;
+use serde;
+use serde::de::{Deserializer, Visitor};
+
+use AbsOscTime;
+
+/// Reserved newtype names, recognized by the codec.
+pub(crate) const MIDI_NAME: &str = "$__osc_address::OscMidi";
+pub(crate) const COLOR_NAME: &str = "$__osc_address::OscColor";
+pub(crate) const SYMBOL_NAME: &str = "$__osc_address::OscSymbol";
+pub(crate) const TIME_TAG_NAME: &str = "$__osc_address::OscTimeTag";
+/// Reserved unit struct name, recognized by the codec.
+pub(crate) const IMPULSE_NAME: &str = "$__osc_address::OscImpulse";
+/// Reserved newtype name, with which an `OscValue` asks a deserializer for
+/// the type tag of an argument. The codec then visits the argument as an enum
+/// variant named by its type tag.
+pub(crate) const VALUE_NAME: &str = "$__osc_address::OscValue";
+
+/// Arbitrary binary data, encoded with the OSC type tag 'b'.
+///
+/// Serde treats a `Vec<u8>` as a sequence of integers, which `encode` writes
+/// as a blob but other backends, such as serde_osc, may not. This is instead
+/// serialized as a serde byte array, which is a blob in any backend.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct OscBlob(pub Vec<u8>);
+
+/// A 4-byte MIDI message, encoded with the OSC type tag 'm'.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct OscMidi {
+    /// The MIDI port ID.
+    pub port: u8,
+    /// The status byte, e.g. `0x90` for a Note On event on channel 1.
+    pub status: u8,
+    /// The first data byte.
+    pub data1: u8,
+    /// The second data byte.
+    pub data2: u8,
+}
+
+/// A 32-bit RGBA color, encoded with the OSC type tag 'r'.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct OscColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+/// An ASCII character, encoded with the OSC type tag 'c'.
+///
+/// A plain `char` is encoded the same way; this type exists for symmetry
+/// with the other OSC types.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OscChar(pub char);
+
+/// A symbol, encoded with the OSC type tag 'S'. Symbols are strings, but are
+/// distinguished from them by systems such as Max/MSP.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OscSymbol(pub String);
+
+/// An impulse (also known as "infinitum" or "bang"), encoded with the OSC
+/// type tag 'I'. It carries no data.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct OscImpulse;
+
+/// An absolute time, encoded as an argument with the OSC type tag 't'.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OscTimeTag(pub AbsOscTime);
+
+/// Serializes a byte slice as a serde byte array.
+struct Bytes<'a>(&'a [u8]);
+
+impl<'a> serde::Serialize for Bytes<'a> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+impl From<Vec<u8>> for OscBlob {
+    fn from(data: Vec<u8>) -> Self {
+        OscBlob(data)
+    }
+}
+
+impl OscMidi {
+    /// Create a MIDI message from its port ID, status byte and data bytes.
+    pub fn new(port: u8, status: u8, data1: u8, data2: u8) -> Self {
+        Self{ port, status, data1, data2 }
+    }
+    /// The message as it is laid out in OSC: port, status, data1, data2.
+    pub fn to_bytes(&self) -> [u8; 4] {
+        [self.port, self.status, self.data1, self.data2]
+    }
+}
+
+impl OscColor {
+    /// Create a color from its red, green, blue and alpha components.
+    pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self{ r, g, b, a }
+    }
+    /// The color packed as it is laid out in OSC, i.e. `0xRRGGBBAA`.
+    pub fn to_rgba(&self) -> u32 {
+        u32::from_be_bytes([self.r, self.g, self.b, self.a])
+    }
+    /// Unpack a color laid out as `0xRRGGBBAA`.
+    pub fn from_rgba(rgba: u32) -> Self {
+        let [r, g, b, a] = rgba.to_be_bytes();
+        Self{ r, g, b, a }
+    }
+}
+
+impl From<char> for OscChar {
+    fn from(c: char) -> Self {
+        OscChar(c)
+    }
+}
+
+impl<'a> From<&'a str> for OscSymbol {
+    fn from(symbol: &'a str) -> Self {
+        OscSymbol(symbol.to_owned())
+    }
+}
+
+impl From<String> for OscSymbol {
+    fn from(symbol: String) -> Self {
+        OscSymbol(symbol)
+    }
+}
+
+impl From<AbsOscTime> for OscTimeTag {
+    fn from(time: AbsOscTime) -> Self {
+        OscTimeTag(time)
+    }
+}
+
+impl OscTimeTag {
+    /// The time tag as the 64-bit fixed point number that OSC transmits:
+    /// seconds in the upper 32 bits, and fractional seconds in the lower 32.
+    pub fn to_bits(&self) -> u64 {
+        ((self.0.sec() as u64) << 32) | self.0.frac() as u64
+    }
+    /// Unpack a time tag from the 64-bit fixed point number that OSC transmits.
+    pub fn from_bits(bits: u64) -> Self {
+        OscTimeTag(AbsOscTime::new((bits >> 32) as u32, bits as u32))
+    }
+}
+
+impl serde::Serialize for OscBlob {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl serde::Serialize for OscMidi {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(MIDI_NAME, &Bytes(&self.to_bytes()))
+    }
+}
+
+impl serde::Serialize for OscColor {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(COLOR_NAME, &self.to_rgba())
+    }
+}
+
+impl serde::Serialize for OscChar {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_char(self.0)
+    }
+}
+
+impl serde::Serialize for OscSymbol {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(SYMBOL_NAME, &self.0)
+    }
+}
+
+impl serde::Serialize for OscImpulse {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit_struct(IMPULSE_NAME)
+    }
+}
+
+impl serde::Serialize for OscTimeTag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(TIME_TAG_NAME, &self.to_bits())
+    }
+}
+
+/// Visitor for the byte array of a blob or a MIDI message.
+struct BytesVisitor(&'static str);
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.0)
+    }
+    fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+        Ok(v.to_owned())
+    }
+    fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+        Ok(v)
+    }
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Vec<u8>, D::Error> {
+        deserializer.deserialize_bytes(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for OscBlob {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_byte_buf(BytesVisitor("an OSC blob")).map(OscBlob)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for OscMidi {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = deserializer.deserialize_newtype_struct(MIDI_NAME, BytesVisitor("a 4-byte OSC MIDI message"))?;
+        match bytes[..] {
+            [port, status, data1, data2] => Ok(OscMidi::new(port, status, data1, data2)),
+            _ => Err(serde::de::Error::invalid_length(bytes.len(), &"a 4-byte OSC MIDI message")),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for OscColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ColorVisitor;
+        impl<'de> Visitor<'de> for ColorVisitor {
+            type Value = OscColor;
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an OSC RGBA color")
+            }
+            fn visit_u32<E: serde::de::Error>(self, v: u32) -> Result<OscColor, E> {
+                Ok(OscColor::from_rgba(v))
+            }
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<OscColor, E> {
+                if v > u32::MAX as u64 {
+                    return Err(E::invalid_value(serde::de::Unexpected::Unsigned(v), &self));
+                }
+                self.visit_u32(v as u32)
+            }
+            fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<OscColor, D::Error> {
+                deserializer.deserialize_u32(self)
+            }
+        }
+        deserializer.deserialize_newtype_struct(COLOR_NAME, ColorVisitor)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for OscChar {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        char::deserialize(deserializer).map(OscChar)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for OscSymbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SymbolVisitor;
+        impl<'de> Visitor<'de> for SymbolVisitor {
+            type Value = OscSymbol;
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an OSC symbol")
+            }
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<OscSymbol, E> {
+                Ok(OscSymbol(v.to_owned()))
+            }
+            fn visit_string<E: serde::de::Error>(self, v: String) -> Result<OscSymbol, E> {
+                Ok(OscSymbol(v))
+            }
+            fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<OscSymbol, D::Error> {
+                deserializer.deserialize_string(self)
+            }
+        }
+        deserializer.deserialize_newtype_struct(SYMBOL_NAME, SymbolVisitor)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for OscImpulse {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ImpulseVisitor;
+        impl<'de> Visitor<'de> for ImpulseVisitor {
+            type Value = OscImpulse;
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an OSC impulse")
+            }
+            fn visit_unit<E: serde::de::Error>(self) -> Result<OscImpulse, E> {
+                Ok(OscImpulse)
+            }
+        }
+        deserializer.deserialize_unit_struct(IMPULSE_NAME, ImpulseVisitor)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for OscTimeTag {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TimeTagVisitor;
+        impl<'de> Visitor<'de> for TimeTagVisitor {
+            type Value = OscTimeTag;
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an OSC time tag")
+            }
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<OscTimeTag, E> {
+                Ok(OscTimeTag::from_bits(v))
+            }
+            fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<OscTimeTag, D::Error> {
+                deserializer.deserialize_u64(self)
+            }
+        }
+        deserializer.deserialize_newtype_struct(TIME_TAG_NAME, TimeTagVisitor)
+    }
+}
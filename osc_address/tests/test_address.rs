@@ -0,0 +1,46 @@
+extern crate osc_address;
+use osc_address::{OscAddress, OscAddressError};
+
+fn address(address: &str) -> OscAddress {
+    address.parse().unwrap()
+}
+
+#[test]
+fn components() {
+    let addr = address("/renderer/42/say");
+    assert_eq!(addr.components().collect::<Vec<_>>(), vec!["renderer", "42", "say"]);
+    assert_eq!(OscAddress::root().components().count(), 0);
+}
+
+#[test]
+fn split_first() {
+    let addr = address("/renderer/42/say");
+    let (first, rest) = addr.split_first().unwrap();
+    assert_eq!(first, "renderer");
+    assert_eq!(rest, "/42/say");
+    assert_eq!(address("/say").split_first(), Some(("say", OscAddress::root())));
+    assert_eq!(OscAddress::root().split_first(), None);
+}
+
+#[test]
+fn parent_and_child() {
+    let addr = address("/renderer/42");
+    assert_eq!(addr.parent(), Some(address("/renderer")));
+    assert_eq!(address("/renderer").parent(), Some(OscAddress::root()));
+    assert_eq!(OscAddress::root().parent(), None);
+    assert_eq!(addr.join("say").unwrap(), "/renderer/42/say");
+    assert_eq!(OscAddress::root().join("renderer").unwrap(), "/renderer");
+}
+
+#[test]
+fn invalid() {
+    assert_eq!(OscAddress::new("renderer"), Err(OscAddressError::MissingLeadingSlash));
+    assert_eq!(OscAddress::new("/renderer//say"), Err(OscAddressError::EmptyComponent(10)));
+    assert_eq!(OscAddress::new("/renderer/"), Err(OscAddressError::EmptyComponent(10)));
+    assert_eq!(OscAddress::new("/renderer/*"), Err(OscAddressError::ForbiddenChar('*', 10)));
+    assert_eq!(OscAddress::new("/hello world"), Err(OscAddressError::ForbiddenChar(' ', 6)));
+    let mut addr = address("/renderer");
+    assert_eq!(addr.push("a/b"), Err(OscAddressError::ForbiddenChar('/', 11)));
+    assert_eq!(addr.push("#1"), Err(OscAddressError::ForbiddenChar('#', 10)));
+    assert_eq!(addr, "/renderer");
+}
@@ -0,0 +1,189 @@
+extern crate osc_address;
+extern crate serde;
+use osc_address::{decode, encode, OscBundle, OscCodecError, OscPacket, OscTime};
+
+/// Byte array which serializes as an OSC blob.
+#[derive(Debug, PartialEq)]
+struct Blob(Vec<u8>);
+
+impl serde::Serialize for Blob {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Blob {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BlobVisitor;
+        impl<'de> serde::de::Visitor<'de> for BlobVisitor {
+            type Value = Blob;
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a blob")
+            }
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Blob, E> {
+                Ok(Blob(v.to_owned()))
+            }
+        }
+        deserializer.deserialize_bytes(BlobVisitor)
+    }
+}
+
+type Msg = (String, (i32,));
+type ArrayMsg = (String, (Vec<i32>, Vec<Vec<i32>>, i32));
+
+#[test]
+fn message() {
+    let encoded = encode(&("/foo", (1i32, 2.5f32, "hi"))).unwrap();
+    assert_eq!(encoded, b"/foo\0\0\0\0,ifs\0\0\0\0\0\0\0\x01\x40\x20\0\0hi\0\0".to_vec());
+    let decoded: (String, (i32, f32, String)) = decode(&encoded).unwrap();
+    assert_eq!(decoded, ("/foo".to_owned(), (1, 2.5, "hi".to_owned())));
+    // Strings may be borrowed from the packet.
+    let decoded: (&str, (i32, f32, &str)) = decode(&encoded).unwrap();
+    assert_eq!(decoded, ("/foo", (1, 2.5, "hi")));
+}
+
+#[test]
+fn blob() {
+    let encoded = encode(&("/b", (Blob(vec![1, 2, 3, 4, 5]),))).unwrap();
+    assert_eq!(encoded, b"/b\0\0,b\0\0\0\0\0\x05\x01\x02\x03\x04\x05\0\0\0".to_vec());
+    let decoded: (String, (Blob,)) = decode(&encoded).unwrap();
+    assert_eq!(decoded.1, (Blob(vec![1, 2, 3, 4, 5]),));
+}
+
+#[test]
+fn bytes_as_blob() {
+    // Serde serializes these as sequences of u8, which are encoded as blobs.
+    let blob = b"/b\0\0,b\0\0\0\0\0\x05\x01\x02\x03\x04\x05\0\0\0".to_vec();
+    let data: &[u8] = &[1, 2, 3, 4, 5];
+    assert_eq!(encode(&("/b", (data,))).unwrap(), blob);
+    assert_eq!(encode(&("/b", (data.to_vec(),))).unwrap(), blob);
+    assert_eq!(encode(&("/b", ([1u8, 2, 3, 4, 5],))).unwrap(), blob);
+    let decoded: (&str, (&[u8],)) = decode(&blob).unwrap();
+    assert_eq!(decoded.1, (data,));
+    let decoded: (String, (Vec<u8>,)) = decode(&blob).unwrap();
+    assert_eq!(decoded.1, (data.to_vec(),));
+    let decoded: (String, ([u8; 5],)) = decode(&blob).unwrap();
+    assert_eq!(decoded.1, ([1, 2, 3, 4, 5],));
+    assert!(decode::<(String, ([u8; 4],))>(&blob).is_err());
+    // An array of blobs.
+    let encoded = encode(&("/b", (vec![vec![1u8], vec![2, 3]],))).unwrap();
+    assert_eq!(&encoded[4..12], b",[bb]\0\0\0");
+    let decoded: (String, (Vec<Vec<u8>>,)) = decode(&encoded).unwrap();
+    assert_eq!(decoded.1, (vec![vec![1], vec![2, 3]],));
+    // The type of an empty sequence's elements is unknown, so it's an empty array.
+    let empty: &[u8] = &[];
+    let encoded = encode(&("/b", (empty,))).unwrap();
+    assert_eq!(encoded, b"/b\0\0,[]\0".to_vec());
+    let decoded: (String, (&[u8],)) = decode(&encoded).unwrap();
+    assert_eq!(decoded.1, (empty,));
+}
+
+#[test]
+fn no_arguments() {
+    let encoded = encode(&("/a", ())).unwrap();
+    assert_eq!(encoded, b"/a\0\0,\0\0\0".to_vec());
+    let decoded: (String, ()) = decode(&encoded).unwrap();
+    assert_eq!(decoded.0, "/a");
+    // Older implementations may omit the type tag string entirely.
+    let decoded: (String, ()) = decode(b"/a\0\0").unwrap();
+    assert_eq!(decoded.0, "/a");
+}
+
+#[test]
+fn bundle() {
+    let inner = OscBundle::new(OscTime::Now, vec![OscPacket::Message(("/b".to_owned(), (2,)))]);
+    let packet: OscPacket<Msg> = OscPacket::Bundle(OscBundle::new(OscTime::new(5, 6), vec![
+        OscPacket::Message(("/a".to_owned(), (1,))),
+        OscPacket::Bundle(inner),
+    ]));
+    let encoded = encode(&packet).unwrap();
+    let message_a = b"/a\0\0,i\0\0\0\0\0\x01";
+    assert_eq!(&encoded[..16], b"#bundle\0\0\0\0\x05\0\0\0\x06");
+    assert_eq!(&encoded[16..20], &[0, 0, 0, 12]);
+    assert_eq!(&encoded[20..32], message_a);
+    assert_eq!(&encoded[32..36], &[0, 0, 0, 32]);
+    assert_eq!(encoded.len(), 68);
+
+    let decoded: OscPacket<Msg> = decode(&encoded).unwrap();
+    let bundle = match decoded {
+        OscPacket::Bundle(bundle) => bundle,
+        OscPacket::Message(_) => panic!("expected a bundle"),
+    };
+    assert_eq!(bundle.time_tag().as_system_time(), None);
+    match bundle.messages()[..] {
+        [OscPacket::Message(ref a), OscPacket::Bundle(ref inner)] => {
+            assert_eq!(*a, ("/a".to_owned(), (1,)));
+            match inner.messages()[..] {
+                [OscPacket::Message(ref b)] => assert_eq!(*b, ("/b".to_owned(), (2,))),
+                _ => panic!("unexpected inner bundle contents: {:?}", inner),
+            }
+        },
+        _ => panic!("unexpected bundle contents: {:?}", bundle),
+    }
+}
+
+#[test]
+fn unsupported() {
+    assert_eq!(encode(&("/a", (1u8,))), Err(OscCodecError::UnsupportedType("u8")));
+    assert_eq!(encode(&("/a", ((1u8, 2),))), Err(OscCodecError::UnsupportedType("u8")));
+    assert_eq!(encode(&("a", (1,))), Err(OscCodecError::BadFormat));
+}
+
+#[test]
+fn arrays() {
+    let encoded = encode(&("/a", (vec![1, 2], vec![vec![3], vec![]], 4))).unwrap();
+    assert_eq!(&encoded[..20], b"/a\0\0,[ii][[i][]]i\0\0\0");
+    assert_eq!(&encoded[20..], &[0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 4]);
+    let decoded: ArrayMsg = decode(&encoded).unwrap();
+    assert_eq!(decoded.1, (vec![1, 2], vec![vec![3], vec![]], 4));
+    // Fixed-size arrays are encoded the same way.
+    assert_eq!(&encode(&("/a", ([1, 2], [[3], [4]], 5))).unwrap()[4..20], b",[ii][[i][i]]i\0\0");
+    // Elements that the payload doesn't consume are an error, as for the top-level arguments.
+    let err = decode::<(String, ((i32,), i32))>(b"/a\0\0,[ii]i\0\0\0\0\0\x01\0\0\0\x02\0\0\0\x03").unwrap_err();
+    assert_eq!(err, OscCodecError::Message("1 unexpected trailing arguments".to_owned()));
+    let err = decode::<(String, ([i32; 2],))>(&encode(&("/a", (vec![1, 2, 3],))).unwrap()).unwrap_err();
+    assert_eq!(err, OscCodecError::Message("1 unexpected trailing arguments".to_owned()));
+    let err = decode::<(String, ([[i32; 1]; 1],))>(&encode(&("/a", (vec![vec![1], vec![2, 3]],))).unwrap()).unwrap_err();
+    assert_eq!(err, OscCodecError::Message("1 unexpected trailing arguments".to_owned()));
+}
+
+#[test]
+fn malformed() {
+    let decode_msg = |packet: &[u8]| decode::<Msg>(packet).unwrap_err();
+    // Garbage after the address's terminating NUL.
+    assert_eq!(decode_msg(b"/a\0x,i\0\0\0\0\0\x01"), OscCodecError::BadPadding);
+    // Address which is not padded to 4 bytes.
+    assert_eq!(decode_msg(b"/ab\0\0"), OscCodecError::BadPadding);
+    assert_eq!(decode_msg(b"/a\0\0,x\0\0\0\0\0\x01"), OscCodecError::UnknownTypeTag('x'));
+    assert_eq!(decode_msg(b"/a\0\0,i\0\0\0\0"), OscCodecError::UnexpectedEof);
+    assert_eq!(decode_msg(b"/a\0\0i\0\0\0\0\0\0\x01"), OscCodecError::BadFormat);
+    assert_eq!(decode_msg(b"a\0\0\0,i\0\0\0\0\0\x01"), OscCodecError::BadFormat);
+    assert_eq!(decode_msg(b"/a\0\0,s\0\0\xff\0\0\0"), OscCodecError::BadUtf8);
+    // Unbalanced array brackets.
+    assert_eq!(decode::<(String, (Vec<i32>,))>(b"/a\0\0,[i\0\0\0\0\x01").unwrap_err(), OscCodecError::BadFormat);
+    assert_eq!(decode::<(String, Vec<i32>)>(b"/a\0\0,i]\0\0\0\0\0\x01").unwrap_err(), OscCodecError::BadFormat);
+}
+
+#[test]
+fn trailing_arguments() {
+    let encoded = encode(&("/a", (0.5f32, 3i32))).unwrap();
+    let err = decode::<(String, (f32,))>(&encoded).unwrap_err();
+    assert_eq!(err, OscCodecError::Message("1 unexpected trailing arguments".to_owned()));
+    // An array counts as a single argument.
+    let encoded = encode(&("/a", (1, vec![2, 3], 4))).unwrap();
+    let err = decode::<(String, (i32,))>(&encoded).unwrap_err();
+    assert_eq!(err, OscCodecError::Message("2 unexpected trailing arguments".to_owned()));
+    // Arguments are consumed even if the payload isn't visited.
+    assert!(decode::<(String,)>(&encoded).is_err());
+    assert!(decode::<(String, ())>(&encoded).is_err());
+}
+
+#[test]
+fn trailing_data() {
+    let mut encoded = encode(&("/a", (1,))).unwrap();
+    encoded.extend_from_slice(b"junk");
+    assert_eq!(decode::<Msg>(&encoded).unwrap_err(), OscCodecError::BadFormat);
+    let mut encoded = encode(&("/a", ())).unwrap();
+    encoded.extend_from_slice(b"junk");
+    assert_eq!(decode::<(String, ())>(&encoded).unwrap_err(), OscCodecError::BadFormat);
+}
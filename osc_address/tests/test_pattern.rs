@@ -0,0 +1,98 @@
+extern crate osc_address;
+use osc_address::{pattern_matches, OscAddressPattern, OscPatternError};
+
+fn pattern(pattern: &str) -> OscAddressPattern {
+    pattern.parse().unwrap()
+}
+
+#[test]
+fn literal() {
+    let pat = pattern("/renderer/new");
+    assert!(pat.matches("/renderer/new"));
+    assert!(!pat.matches("/renderer/del"));
+    assert!(!pat.matches("/renderer"));
+    assert!(!pat.matches("/renderer/new/extra"));
+    assert!(pat.components().iter().all(|component| component.is_literal()));
+}
+
+#[test]
+fn wildcards() {
+    let pat = pattern("/*/n?w*");
+    assert!(pat.matches("/renderer/new"));
+    assert!(pat.matches("/routegraph/now_or_never"));
+    assert!(!pat.matches("/renderer/nw"));
+    assert!(!pat.components()[1].is_literal());
+}
+
+#[test]
+fn sets() {
+    let pat = pattern("/track[0-3x]/[!a-z-]");
+    assert!(pat.matches("/track2/A"));
+    assert!(pat.matches("/trackx/7"));
+    assert!(!pat.matches("/track4/A"));
+    assert!(!pat.matches("/track2/q"));
+    assert!(!pat.matches("/track2/-"));
+}
+
+#[test]
+fn options() {
+    let pat = pattern("/routegraph/{add,del}_{node,edge}");
+    assert!(pat.matches("/routegraph/add_node"));
+    assert!(pat.matches("/routegraph/del_edge"));
+    assert!(!pat.matches("/routegraph/add_graph"));
+}
+
+#[test]
+fn root() {
+    let pat = pattern("/");
+    assert!(pat.components().is_empty());
+    assert!(pat.matches("/"));
+    assert!(!pat.matches("/renderer"));
+}
+
+#[test]
+fn syntax_errors() {
+    assert_eq!(OscAddressPattern::new("renderer"), Err(OscPatternError::MissingLeadingSlash));
+    assert_eq!(OscAddressPattern::new("/renderer/[0-9/say"), Err(OscPatternError::Unclosed('[', 10)));
+    assert_eq!(OscAddressPattern::new("/{new,del"), Err(OscPatternError::Unclosed('{', 1)));
+    assert_eq!(OscAddressPattern::new("/new]"), Err(OscPatternError::Unexpected(']', 4)));
+    assert_eq!(OscAddressPattern::new("/{a,[b]}"), Err(OscPatternError::Unexpected('[', 4)));
+}
+
+#[test]
+fn empty_components() {
+    assert_eq!(OscAddressPattern::new("/a//b"), Err(OscPatternError::EmptyComponent(3)));
+    assert_eq!(OscAddressPattern::new("/a/"), Err(OscPatternError::EmptyComponent(3)));
+    assert_eq!(OscAddressPattern::new("//a"), Err(OscPatternError::EmptyComponent(1)));
+    // Only the root pattern has no components.
+    assert!(pattern("/").components().is_empty());
+}
+
+#[test]
+fn forbidden_chars() {
+    assert_eq!(OscAddressPattern::new("/new track"), Err(OscPatternError::ForbiddenChar(' ', 4)));
+    assert_eq!(OscAddressPattern::new("/a/#1"), Err(OscPatternError::ForbiddenChar('#', 3)));
+    assert_eq!(OscAddressPattern::new("/*,b"), Err(OscPatternError::ForbiddenChar(',', 2)));
+    // ',' separates the options of a "{...}", and sets may contain any character.
+    assert!(pattern("/{a,b}").matches("/b"));
+    assert!(pattern("/[#,]").matches("/#"));
+    assert!(!pattern_matches("a b", "a b"));
+}
+
+#[test]
+fn many_wildcards() {
+    // Each '*' used to be matched by trying every split of the component in
+    // turn, which took exponential time for a pattern such as this one.
+    let pat = pattern("/*a*a*a*a*a*a*a*a*b");
+    let component = "a".repeat(4096);
+    assert!(!pat.matches(&format!("/{}", component)));
+    assert!(pat.matches(&format!("/{}b", component)));
+    assert!(pattern("/*{a,aa}*[!b]?").matches(&format!("/x{}", component)));
+}
+
+#[test]
+fn many_wildcards_in_component() {
+    let component = "a".repeat(4096);
+    assert!(!pattern_matches("*a*a*a*a*a*a*a*a*a*a*a*a*b", &component));
+    assert!(pattern_matches("*a*a*a*a*a*a*a*a*a*a*a*a*", &component));
+}
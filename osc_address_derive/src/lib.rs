@@ -100,6 +100,8 @@
 //! 
 //! `PathArgument` may be any type that implements both `std::fmt::Display` and
 //! `std::str::FromStr`, e.g. `i32`, `f64`, `String`, etc.
+//! Its `Display` output must be a valid OSC address component (see `osc_address::OscAddress`);
+//! otherwise, building the address of the message (and hence serializing it) fails.
 //! For example, `VariantName(u8, MsgPayload)` will match any OSC address beginning with
 //! "/xxx[/...]" where "xxx" is a valid `u8`. In the special case that `PathArgument=()`, the
 //! variant must be explicitly annotated with its address:
@@ -110,6 +112,100 @@
 //! VariantName((), MsgPayload)
 //! ```
 //! 
+//! Variants without path arguments may also omit the `()` placeholder. A newtype
+//! variant holds only the `MsgPayload`, and a unit variant is a message without
+//! arguments (encoded with an empty type tag string, ","):
+//! 
+//! ```notest
+//! // This enum variant will match any address "/play[/...]".
+//! #[osc_address(address="play")]
+//! Play(MsgPayload),
+//! // This enum variant will match the address "/reset", with no arguments.
+//! #[osc_address(address="reset")]
+//! Reset,
+//! ```
+//! 
+//! The address may span several components, which saves declaring an enum for
+//! each level of a deep but sparse namespace:
+//! 
+//! ```notest
+//! // This enum variant will match any address "/mixer/master/volume[/...]".
+//! #[osc_address(address="mixer/master/volume")]
+//! VariantName((), MsgPayload)
+//! ```
+//! 
+//! Rather than annotating each such variant, the enum itself may be annotated with
+//! `#[osc_address(rename_all="...")]`, in which case variants without path arguments
+//! take their address from their name, as with serde's `rename_all`. The supported
+//! conventions are "lowercase", "UPPERCASE", "PascalCase", "camelCase", "snake_case",
+//! "SCREAMING_SNAKE_CASE", "kebab-case" and "SCREAMING-KEBAB-CASE":
+//! 
+//! ```notest
+//! #[derive(OscMessage)]
+//! #[osc_address(rename_all="snake_case")]
+//! enum OscRouteGraph {
+//!     // This enum variant will match any address "/add_node".
+//!     AddNode((), (i32,)),
+//!     // An explicit address takes precedence.
+//!     #[osc_address(address="del")]
+//!     DelNode((), (i32,)),
+//! }
+//! ```
+//! 
+//! A component may also embed the path argument within literal text, as in
+//! "/ch12/vol" or "/track_3/mute". Each `{}` placeholder is parsed from, and
+//! rendered into, the corresponding portion of the component. Several placeholders
+//! bind to the elements of a tuple path argument, in order:
+//! 
+//! ```notest
+//! // This enum variant will match any address "/ch<u8>[/...]".
+//! #[osc_address(address="ch{}")]
+//! Channel(u8, MsgPayload),
+//! // This enum variant will match any address "/cell<u8>_<u8>[/...]".
+//! #[osc_address(address="cell{}_{}")]
+//! Cell((u8, u8), MsgPayload),
+//! ```
+//! 
+//! Each placeholder matches the shortest text that is followed by the next
+//! literal, so adjacent placeholders must be separated by literal text.
+//! 
+//! Placeholders may also span several components, each element of the tuple
+//! being parsed from (and written to) its own component. This avoids declaring
+//! an intermediate enum per coordinate:
+//! 
+//! ```notest
+//! // This enum variant will match the address "/grid/<u8>/<u8>/press".
+//! #[osc_address(address="grid/{}/{}/press")]
+//! Press((u8, u8), (i32,)),
+//! ```
+//! 
+//! ## Custom Path Formats
+//! 
+//! Where a path argument's `FromStr` and `Display` impls don't give the desired
+//! spelling (e.g. zero-padded or hexadecimal numbers), or it has none (e.g. a type
+//! from a foreign crate), the variant may name functions to use in their place:
+//! `parse_with` takes a `fn(&str) -> Result<T, E>`, and `format_with` takes a
+//! `fn(&T, &mut fmt::Formatter) -> fmt::Result`. Alternatively, `with` names a module
+//! providing both, as `parse` and `format`:
+//! 
+//! ```notest
+//! // This enum variant will match the address "/track/007", with a path argument of 7.
+//! #[osc_address(address="track/{}", with="zero_padded")]
+//! Track(u32, (f32,)),
+//! 
+//! mod zero_padded {
+//!     pub fn parse(text: &str) -> Result<u32, std::num::ParseIntError> {
+//!         text.parse()
+//!     }
+//!     pub fn format(index: &u32, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+//!         write!(f, "{:03}", index)
+//!     }
+//! }
+//! ```
+//! 
+//! The functions apply to the path argument as a whole, so a tuple path argument
+//! must not be split across several placeholders.
+//! 
 //! ## MsgPayload
 //! 
 //! The `MsgPayload` component of the enum variant captures all the OSC arguments
@@ -152,42 +248,326 @@
 //! 
 //! except that the captured arguments are named fields instead of tuple arguments.
 //! 
+//! Whether the `MsgPayload` is an argument list or a nested `OscMessage` is inferred
+//! from its syntax: tuples, slices and arrays are argument lists, and any other type
+//! is a nested `OscMessage`. The inference is overridden with `#[osc_address(payload=...)]`,
+//! e.g. for a type alias of a tuple, a `Vec` or a struct which doesn't derive `OscMessage`:
+//! 
+//! ```notest
+//! // The payload is the argument list, e.g. a `Vec<f32>` or `type Args = (i32, f32)`.
+//! #[osc_address(address="levels", payload="args")]
+//! Levels((), Vec<f32>),
+//! // The payload is a nested OscMessage, which routes the remainder of the address.
+//! #[osc_address(address="mixer", payload="message")]
+//! Mixer((), MixerAlias),
+//! // The payload is a serde struct whose fields are the arguments, in declaration order.
+//! #[osc_address(address="control", payload="struct")]
+//! Control((), ControlArgs),
+//! ```
+//! 
+//! ## Aliases
+//! 
+//! A variant may accept further addresses when deserializing, e.g. while migrating
+//! a namespace or to support legacy controllers. Each `alias` takes the same form
+//! as the `address`, but only the `address` is ever emitted when serializing:
+//! 
+//! ```notest
+//! // Matches "/volume" and "/vol", but is always serialized to "/volume".
+//! #[osc_address(address="volume", alias="vol")]
+//! Volume((), (f32,)),
+//! // Matches "/fader<u8>" and the legacy "/1/fader<u8>".
+//! #[osc_address(address="fader{}", alias="1/fader{}")]
+//! Fader(u8, (f32,)),
+//! ```
+//! 
+//! ## Overlapping Routes
+//! 
+//! Variants are tried in declaration order, so a variant whose path argument parses
+//! the whole component, e.g. `ById(u32, (f32,))`, would shadow any later variant with
+//! an address like "42". Likewise, "ch{}" with a `u8` path argument shadows "ch3", and a
+//! variant with a nested message at "mixer" shadows "mixer/master/volume". The derive
+//! rejects such shadowed addresses, for path arguments of primitive types and `String`,
+//! as well as addresses shared by several variants. A primitive type is recognized by
+//! its plain name (unless a type parameter of the enum takes that name) or its full path,
+//! e.g. `u32` or `std::primitive::u32`; a derive can't see through type aliases, so
+//! overlaps via an alias such as `type Id = u32;` go unreported.
+//! Declare the literal routes first, or opt out on the shadowed variant when the overlap
+//! is intended:
+//! 
+//! ```notest
+//! #[osc_address(address="42", allow_overlap)]
+//! Answer((), (f32,)),
+//! ```
+//! 
+//! ## Struct-like Variants
+//! 
+//! Enum variants may instead have named fields. Fields annotated with
+//! `#[osc_address(path)]` are parsed from the address, exactly as the elements of
+//! a tuple `PathArgument`, while the remaining fields form the message arguments
+//! in declaration order:
+//! 
+//! ```notest
+//! // Matches "/ch<u8>/gain", with a single f32 argument.
+//! #[osc_address(address="ch{}/gain")]
+//! SetGain { #[osc_address(path)] channel: u8, gain: f32 },
+//! // Matches "/note", with two i32 arguments.
+//! #[osc_address(address="note")]
+//! Note { pitch: i32, velocity: i32 },
+//! ```
+//! 
+//! Such variants are always leaves of the address; they cannot nest another `OscMessage`.
+//! 
+//! ## Generic Types
+//! 
+//! Types deriving `OscMessage` may have type and lifetime parameters, e.g. to reuse
+//! a sub-namespace with different payloads. The derived impls carry the type's own
+//! bounds and where-clauses, and additionally bound each field whose type involves
+//! a type parameter by what its position requires: `Display + FromStr` for path
+//! arguments, `Serialize + DeserializeOwned` for message arguments, and `OscMessage`
+//! for nested messages:
+//! 
+//! ```notest
+//! #[derive(OscMessage)]
+//! enum Channel<P> {
+//!     // Matches "/ch<u8>[/...]", routing the remainder to P.
+//!     #[osc_address(address="ch{}")]
+//!     Strip(u8, P),
+//! }
+//! ```
+//! 
+//! ## Borrowed Arguments
+//! 
+//! Message arguments may borrow strings and blobs directly from the packet being
+//! decoded rather than copying them, by using references in the payload:
+//! 
+//! ```notest
+//! #[derive(OscMessage)]
+//! enum OscStorage<'a> {
+//!     // Matches "/chunk" with a single blob argument.
+//!     #[osc_address(address="chunk")]
+//!     Chunk((), (&'a [u8],)),
+//! }
+//! ```
+//! 
+//! Such messages can only be deserialized from a backend which supports borrowing,
+//! e.g. `osc_address::decode`, and cannot be matched by address patterns.
+//! 
 //! # Serialization
 //! 
 //! The above explanation of address matching and message decoding assumed deserializing
 //! a message into `OscMessage` types. Of course, `OscMessage`s can be serialized through
 //! any Serde backend as well.
 //! 
+//! # Address Patterns
+//! 
+//! OSC 1.0 allows the address of an incoming message to be a pattern, e.g.
+//! "/routegraph/{add_node,del_node}" or "/renderer/*/say", in which case the
+//! message is to be dispatched to every address that the pattern matches.
+//! Deserializing an `osc_address::OscMatches<OscToplevel>` instead of an `OscToplevel`
+//! yields every such message, each with its own copy of the arguments:
+//! 
+//! ```notest
+//! let matches: OscMatches<OscToplevel> = serde_osc::from_slice(&packet[..]).unwrap();
+//! for message in matches.into_messages() {
+//!     dispatch(message);
+//! }
+//! ```
+//! 
+//! Literal components are matched against the pattern as expected. Path arguments
+//! generally cannot be enumerated, so they are instead parsed from the raw pattern
+//! component; a `String` path argument matching "/renderer/*/say" receives `"*"`.
+//! The exception is a path argument implementing `osc_address::OscPathArg` (see below),
+//! which is matched against each of its values.
+//! Variants whose payload cannot be decoded from the arguments are skipped.
+//! 
+//! # Enumerated Path Arguments
+//! 
+//! Routing on a closed set of names, e.g. "/synth/<osc|filter|env>/gain", needs
+//! neither a variant per name nor hand-written `FromStr` and `Display` impls:
+//! `#[derive(OscPathArg)]` implements them, along with `osc_address::OscPathArg`,
+//! for a C-like enum. Each variant is spelled as its name, unless the enum is annotated
+//! with `#[osc_address(rename_all="...")]` or the variant with `#[osc_address(rename="...")]`:
+//! 
+//! ```notest
+//! #[derive(OscPathArg)]
+//! #[osc_address(rename_all="lowercase")]
+//! enum Module {
+//!     Osc,
+//!     Filter,
+//!     #[osc_address(rename="adsr")]
+//!     Env,
+//! }
+//! 
+//! #[derive(OscMessage)]
+//! enum OscSynth {
+//!     // Matches "/osc[/...]", "/filter[/...]" and "/adsr[/...]".
+//!     Module(Module, OscModule),
+//! }
+//! ```
+//! 
+//! As `OscPathArg::NAMES` lists the values, the address pattern "/{osc,adsr}/gain"
+//! yields a message for each of the two modules. This applies to path arguments which
+//! form a whole component, i.e. of variants without an `address`; a placeholder within
+//! a format-string address is parsed from the raw pattern text, as for other types.
+//! 
+//! # Fallback Variants
+//! 
+//! By default, a message whose address matches no variant fails to deserialize.
+//! Instead, one variant of an enum may be annotated with `#[osc_address(fallback)]`
+//! to capture such messages, e.g. so that a server can log or forward them:
+//! 
+//! ```notest
+//! #[derive(OscMessage)]
+//! enum OscToplevel {
+//!     #[osc_address(address="control")]
+//!     Control((), (i32, f32)),
+//!     #[osc_address(fallback)]
+//!     Unknown(String, osc_address::OscRawArgs),
+//! }
+//! ```
+//! 
+//! The path argument receives the portion of the address which was not routed,
+//! e.g. "/foo/bar" for a message to "/foo/bar", and may be any type that implements
+//! both `AsRef<str>` and `FromStr`, such as `String` or `osc_address::OscAddress`.
+//! The payload receives the arguments; `OscRawArgs` accepts arguments of any type.
+//! A fallback in a nested enum captures only what reaches that enum, e.g. "/zzz"
+//! of a message to "/renderer/zzz". When matching an address pattern, the fallback
+//! is used only if no other variant matches, and receives the remainder of the pattern.
+//! 
+//! # Capturing the Rest of the Address
+//! 
+//! A variant may proxy a whole subtree of addresses, e.g. to forward everything below
+//! "/plugin/<id>" to a plugin host. The field marked `#[osc_address(rest)]` captures
+//! every component which follows the variant's own address, verbatim:
+//! 
+//! ```notest
+//! #[derive(OscMessage)]
+//! enum OscToplevel {
+//!     // Matches "/host[/...]", e.g. Host("/a/b", ..) for a message to "/host/a/b".
+//!     #[osc_address(address="host")]
+//!     Host(#[osc_address(rest)] OscAddress, osc_address::OscRawArgs),
+//!     // Matches "/plugin/<id>[/...]".
+//!     #[osc_address(address="plugin/{}")]
+//!     Plugin { #[osc_address(path)] id: u32, #[osc_address(rest)] path: String, value: f32 },
+//! }
+//! ```
+//! 
+//! As for a fallback variant, the captured field may be any type that implements both
+//! `AsRef<str>` and `FromStr`, and receives "/" if nothing follows the address. The
+//! payload is the argument list: either `OscRawArgs`, or any other argument type,
+//! including a type parameter of the enum. A variant without an address captures the
+//! whole of the address that reaches it. Unlike a fallback variant, it is tried in
+//! order with the others, so any later variant beneath its address is rejected as
+//! shadowed. When matching an address pattern, the captured field receives the
+//! remainder of the pattern, and so must accept its wildcards to match, e.g. `String`.
+//! 
 //! [osc_address]: https://github.com/Wallacoloo/osc_address
 //! [serde_osc]: https://github.com/Wallacoloo/serde_osc
 
 
 // quote/syn crates require high macro expandion recursion limits
-#![recursion_limit="128"]
+#![recursion_limit="256"]
+extern crate osc_address;
 extern crate proc_macro;
 #[macro_use]
 extern crate quote;
 extern crate syn;
 
-use proc_macro::TokenStream;
+use osc_address::FORBIDDEN_CHARS;
+use proc_macro::{Delimiter, Group, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
 use syn::{MacroInput, MetaItem, NestedMetaItem, Ty};
 
+/// Collects all info from #[osc_address(..)] attributes on the type itself.
+#[derive(Debug, Default)]
+struct ContainerProperties {
+    /// How to derive the address of variants without one, via
+    /// #[osc_address(rename_all="...")].
+    rename_all: Option<RenameRule>,
+}
+
+/// Case conversion applied to variant names to derive their address,
+/// following the conventions of serde's `rename_all`.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
 /// Collects all info from #[osc_address(..)] attributes for a given
 /// enum variant.
 #[derive(Debug)]
 struct OscRouteProperties {
     address: OscBranchFmt,
+    /// Further addresses accepted when deserializing, via #[osc_address(alias=...)].
+    /// Only `address` is ever emitted.
+    aliases: Vec<OscBranchFmt>,
     path_args_type: PathArgsType,
     msg_args_type: MsgArgsType,
+    /// Whether this variant captures the messages that match no other
+    /// variant, via #[osc_address(fallback)].
+    fallback: bool,
+    /// The type of the field which captures the remainder of the address
+    /// verbatim, via #[osc_address(rest)].
+    rest: Option<Ty>,
+    /// Whether this variant may be shadowed by earlier variants, via
+    /// #[osc_address(allow_overlap)].
+    allow_overlap: bool,
+    /// Function which parses the path argument in place of `FromStr`, via
+    /// #[osc_address(parse_with=...)] or #[osc_address(with=...)].
+    parse_with: Option<syn::Path>,
+    /// Function which formats the path argument in place of `Display`, via
+    /// #[osc_address(format_with=...)] or #[osc_address(with=...)].
+    format_with: Option<syn::Path>,
+    shape: VariantShape,
+    /// Lifetimes of the references within the message arguments, which are
+    /// borrowed from the deserializer's input.
+    borrowed: Vec<syn::Lifetime>,
+}
+
+/// Describes how the path argument and payload are laid out in the variant.
+#[derive(Debug)]
+#[derive(PartialEq)]
+enum VariantShape {
+    /// VariantName(PathArgument, MsgPayload)
+    Tuple,
+    /// VariantName(MsgPayload), without path arguments.
+    Newtype,
+    /// VariantName(Rest, MsgPayload), where Rest is marked #[osc_address(rest)].
+    Rest,
+    /// VariantName, without path arguments nor message arguments.
+    Unit,
+    /// VariantName { .. }, where the fields marked #[osc_address(path)] form
+    /// the path argument, the field marked #[osc_address(rest)] (if any)
+    /// captures the remainder of the address, and the remaining fields form
+    /// the message arguments.
+    Struct {
+        path_fields: Vec<syn::Ident>,
+        rest_field: Option<syn::Ident>,
+        arg_fields: Vec<syn::Ident>,
+    },
 }
 
 /// Describes how to format the portion of the OSC address between adjacent
 /// pairs of '/'
 #[derive(Debug)]
+#[derive(PartialEq)]
 enum OscBranchFmt {
     /// This branch of the OSC address is a literal string,
-    /// e.g. "world" in "/hello/world"
+    /// e.g. "world" in "/hello/world". It may span several components,
+    /// e.g. "mixer/master" in "/mixer/master/volume".
     Str(String),
+    /// This branch of the OSC address mixes literal text with placeholders for
+    /// the path argument, e.g. "ch{}" in "/ch12/vol" or "grid/{}/{}/press".
+    /// Holds the literal text around each placeholder of each component,
+    /// e.g. [["ch", ""]] or [["grid"], ["", ""], ["", ""], ["press"]].
+    Fmt(Vec<Vec<String>>),
     /// No format string was provided. Presumably there is a path argument
     /// and it implements FromStr/ToString.
     None,
@@ -198,8 +578,8 @@ enum OscBranchFmt {
 enum PathArgsType {
     /// No path arguments (aka 'unit', ())
     Unit,
-    /// There is a path argument.
-    One,
+    /// There is a path argument, of the given type.
+    One(Ty),
 }
 
 #[derive(Debug)]
@@ -212,6 +592,30 @@ enum MsgArgsType {
     Struct,
 }
 
+/// The kind of payload of a variant, as given by #[osc_address(payload="...")].
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PayloadKind {
+    /// "args": the payload is the argument list, e.g. a tuple or a `Vec`.
+    Args,
+    /// "message": the payload is a nested OscMessage.
+    Message,
+    /// "struct": the payload is a serde struct whose fields are the arguments.
+    Struct,
+}
+
+/// Errors in the input, each alongside the item to report it against.
+type InputErrors = Vec<(ErrorTarget, String)>;
+
+/// The item which an error in the input is reported against.
+enum ErrorTarget {
+    /// The type deriving OscMessage, or its #[osc_address(..)] attribute.
+    Container,
+    /// The enum variant of the given name.
+    Variant(syn::Ident),
+    /// The n-th #[osc_address(..)] attribute of the enum variant of the given name.
+    Attribute(syn::Ident, usize),
+}
+
 /// Create a `impl OscMessage for T` implementation for a given type.
 /// 
 /// This is never called directly, but invoked by preceding the type
@@ -221,6 +625,12 @@ pub fn derive_osc_address(input: TokenStream) -> TokenStream {
     // Parse the string representation into a syntax tree
     let ast = syn::parse_derive_input(&input.to_string()).unwrap();
 
+    // Report every error in the attributes at once, rather than one per build.
+    let errors = check_input(&ast);
+    if !errors.is_empty() {
+        return errors.into_iter().flat_map(|(target, message)| compile_error(&message, error_span(&input, &target))).collect();
+    }
+
     // Build the output
     let expanded = impl_osc_address(&ast);
 
@@ -232,6 +642,7 @@ pub fn derive_osc_address(input: TokenStream) -> TokenStream {
 
 fn impl_osc_address(ast: &MacroInput) -> quote::Tokens {
     let typename = &ast.ident;
+    let container = get_container_props(ast);
     // match the element the #[derive(OscMessage)] statement is applied to,
     // e.g. "enum { ... }" in
     // #[derive(OscMessage)]
@@ -242,34 +653,70 @@ fn impl_osc_address(ast: &MacroInput) -> quote::Tokens {
             // attrs applied to each variant of the enum
             let arms = variants.iter().map(|variant| {
                 let variant_ident = variant.ident.clone();
-                let variant_props = get_variant_props(variant);
+                let variant_props = get_variant_props(variant, &container);
                 let address_push_impl = match variant_props.address {
-                    // This component of the address is a string constant;
-                    // push the string to the address being built.
-                    OscBranchFmt::Str(variant_address) => {
-                        let variant_address = "/".to_string() + &variant_address;
+                    // The path argument holds the remainder of the address,
+                    // e.g. "/foo/bar"; push each of its components.
+                    _ if variant_props.fallback => quote! {
+                        address.push_address(std::convert::AsRef::<str>::as_ref(path_arg))?;
+                    },
+                    // These components of the address are string constants;
+                    // push each of them to the address being built.
+                    OscBranchFmt::Str(ref variant_address) => {
+                        let components = variant_address.split('/');
                         quote!{
-                            address.push_str(#variant_address);
+                            #(address.push(#components)?;)*
+                        }
+                    },
+                    // This component of the address mixes literals with the
+                    // path argument (or each element thereof, if a tuple).
+                    OscBranchFmt::Fmt(ref template) => {
+                        let mut fields = path_arg_fields(num_placeholders(template), &variant_props.path_args_type).into_iter()
+                            .map(|field| display_path_arg(field, &variant_props.format_with));
+                        let pushes = template.iter().map(|literals| {
+                            let fmt = literals.join("{}");
+                            let component_fields = fields.by_ref().take(literals.len() - 1).collect::<Vec<_>>();
+                            if component_fields.is_empty() {
+                                quote! { address.push(#fmt)?; }
+                            } else {
+                                quote! { address.push_display(&format_args!(#fmt, #(#component_fields),*))?; }
+                            }
+                        }).collect::<Vec<_>>();
+                        quote! {
+                            #(#pushes)*
+                        }
+                    },
+                    // The variant captures the whole address in its remainder.
+                    OscBranchFmt::None if variant_props.path_args_type == PathArgsType::Unit => quote! {},
+                    OscBranchFmt::None => {
+                        let path_arg = display_path_arg(quote! { path_arg }, &variant_props.format_with);
+                        quote! {
+                            address.push_display(&#path_arg)?;
                         }
                     },
-                    // This component of the address is a variable;
-                    // write that variable to the address being built.
-                    OscBranchFmt::None => quote! {
-                        address.push_str(&format!("/{}", path_arg));
+                };
+                // The captured remainder of the address, e.g. "/foo/bar", follows.
+                let rest_push_impl = match variant_props.rest {
+                    Some(_) => quote! {
+                        address.push_address(std::convert::AsRef::<str>::as_ref(rest))?;
                     },
+                    None => quote! {},
                 };
                 let recurse_build_impl = match variant_props.msg_args_type {
                     // Payload IS the message data; not a nested OscMessage
                     MsgArgsType::Seq => quote! {},
                     MsgArgsType::Struct => quote! {
-                        osc_address::OscMessage::build_address(msg_data, address);
+                        osc_address::OscMessage::build_address(msg_data, address)?;
                     },
                 };
                 // Create the variant match case that pushes the component name
                 // and then builds the remainder of the address.
+                let (pattern, bindings) = destructure_variant(typename, &variant_ident, &variant_props.shape);
                 quote! {
-                    #typename::#variant_ident(ref path_arg, ref msg_data) => {
+                    #pattern => {
+                        #bindings
                         #address_push_impl
+                        #rest_push_impl
                         #recurse_build_impl
                     },
                 }
@@ -279,12 +726,13 @@ fn impl_osc_address(ast: &MacroInput) -> quote::Tokens {
                 match *self {
                     #(#arms)*
                 }
+                Ok(())
             }
         },
         // #[derive(OscMessage)] on a Struct is used to treat that struct as a
         // message payload; therefore it HAS no address.
         syn::Body::Struct(ref _variant_data) => {
-            quote! { }
+            quote! { Ok(()) }
         }
     };
     let (do_impl_serde, serialize_body_impl) = match ast.body {
@@ -293,17 +741,20 @@ fn impl_osc_address(ast: &MacroInput) -> quote::Tokens {
             // attrs applied to each variant of the enum
             let arms = variants.iter().map(|variant| {
                 let variant_ident = variant.ident.clone();
-                let variant_props = get_variant_props(variant);
+                let variant_props = get_variant_props(variant, &container);
+                let (pattern, bindings) = destructure_variant(typename, &variant_ident, &variant_props.shape);
                 match variant_props.msg_args_type {
                     // Payload IS the message data; not a nested OscMessage
                     MsgArgsType::Seq => quote! {
-                        #typename::#variant_ident(ref _path_arg, ref msg_data) => {
+                        #pattern => {
+                            #bindings
                             serde::ser::SerializeTuple::serialize_element(serializer, msg_data)
                         }
                     },
                     // Payload is a nested OscMessage
                     MsgArgsType::Struct => quote! {
-                        #typename::#variant_ident(ref _path_arg, ref msg_data) => {
+                        #pattern => {
+                            #bindings
                             osc_address::OscMessage::serialize_body(msg_data, serializer)
                         }
                     },
@@ -331,59 +782,194 @@ fn impl_osc_address(ast: &MacroInput) -> quote::Tokens {
             //     return Ok(#typename::#variant_ident((), seq.next_element()?.unwrap()))
             // }
             // // ...
-            let arms = variants.iter().map(|variant| {
+            let variant_arm = |variant: &syn::Variant| {
                 let variant_ident = variant.ident.clone();
-                let variant_props = get_variant_props(variant);
-                match variant_props.msg_args_type {
+                let variant_props = get_variant_props(variant, &container);
+                if let Some(ref rest_ty) = variant_props.rest {
+                    // Whatever follows the variant's own components is captured
+                    // verbatim, e.g. "/foo/bar", and the payload is the argument list.
+                    let capture = |path_arg: quote::Tokens, downstream_address: quote::Tokens| {
+                        let construct = construct_variant(typename, &variant_ident, &variant_props.shape, quote! { (#path_arg, rest) }, quote! {
+                            seq.next_element()?.ok_or_else(|| serde::de::Error::invalid_length(0, &"a sequence representing an OSC message payload"))?
+                        });
+                        quote! {
+                            let rest_address = if #downstream_address.is_empty() { "/" } else { #downstream_address };
+                            if let Ok(rest) = rest_address.parse::<#rest_ty>() {
+                                return Ok(#construct);
+                            }
+                        }
+                    };
+                    let address_arms = variant_props.addresses().map(|variant_address| match *variant_address {
+                        OscBranchFmt::Str(ref literal) => {
+                            let literal_address = format!("/{}", literal);
+                            let capture = capture(quote! { () }, quote! { downstream_address });
+                            quote! {
+                                if let Some(downstream_address) = address.strip_prefix(#literal_address) {
+                                    if downstream_address.is_empty() || downstream_address.starts_with('/') {
+                                        #capture
+                                    }
+                                }
+                            }
+                        },
+                        OscBranchFmt::Fmt(ref template) => {
+                            let parse_path_arg = parse_template(template, &variant_props.path_args_type, &variant_props.parse_with, quote! { osc_address::match_template }, quote! { address });
+                            let capture = capture(quote! { path_arg }, quote! { downstream_address });
+                            quote! {
+                                if let Some((path_arg, downstream_address)) = #parse_path_arg {
+                                    #capture
+                                }
+                            }
+                        },
+                        // The variant captures the whole address.
+                        OscBranchFmt::None if variant_props.path_args_type == PathArgsType::Unit => {
+                            let capture = capture(quote! { () }, quote! { address });
+                            quote! {
+                                {
+                                    #capture
+                                }
+                            }
+                        },
+                        OscBranchFmt::None => {
+                            let parse_path_arg = parse_path_arg(quote! { component_name }, &variant_props.path_args_type, &variant_props.parse_with);
+                            let capture = capture(quote! { path_arg }, quote! { downstream_address });
+                            quote! {
+                                if let Ok(path_arg) = #parse_path_arg {
+                                    #capture
+                                }
+                            }
+                        },
+                    }).collect::<Vec<_>>();
+                    return quote! {
+                        #(#address_arms)*
+                    };
+                }
+                let msg_data = match variant_props.msg_args_type {
+                    MsgArgsType::Seq => quote! { seq.next_element()?.unwrap() },
+                    MsgArgsType::Struct => quote! { osc_address::OscMessage::deserialize_body(downstream_address, seq)? },
+                };
+                let construct_literal = construct_variant(typename, &variant_ident, &variant_props.shape, quote! { () }, msg_data.clone());
+                let construct = construct_variant(typename, &variant_ident, &variant_props.shape, quote! { path_arg }, msg_data);
+                // Any of the variant's addresses (i.e. its aliases) selects it.
+                let address_arms = variant_props.addresses().map(|variant_address| match variant_props.msg_args_type {
                     // Payload IS the message data; not a nested OscMessage
                     // By necessity this is the leaf message, so we we don't need
                     // to split the component name off of the address.
-                    MsgArgsType::Seq => match variant_props.address {
-                        OscBranchFmt::Str(component_name) => quote! {
-                            if component_name == #component_name && downstream_address.is_empty() {
-                                return Ok(#typename::#variant_ident((), seq.next_element()?.unwrap()));
+                    MsgArgsType::Seq => match *variant_address {
+                        OscBranchFmt::Str(ref literal) => {
+                            let literal_address = format!("/{}", literal);
+                            quote! {
+                                if address == #literal_address {
+                                    return Ok(#construct_literal);
+                                }
+                            }
+                        },
+                        OscBranchFmt::Fmt(ref template) => {
+                            let parse_path_arg = parse_template(template, &variant_props.path_args_type, &variant_props.parse_with, quote! { osc_address::match_template }, quote! { address });
+                            quote! {
+                                if let Some((path_arg, "")) = #parse_path_arg {
+                                    return Ok(#construct);
+                                }
                             }
                         },
-                        OscBranchFmt::None => quote! {
-                            // if we can parse the path argument, then the address variant is matched
-                            if let Ok(path_arg) = component_name.parse() {
-                                return Ok(#typename::#variant_ident(path_arg, seq.next_element()?.unwrap()));
+                        OscBranchFmt::None => {
+                            let parse_path_arg = parse_path_arg(quote! { component_name }, &variant_props.path_args_type, &variant_props.parse_with);
+                            quote! {
+                                // if we can parse the path argument, then the address variant is matched
+                                if let Ok(path_arg) = #parse_path_arg {
+                                    return Ok(#construct);
+                                }
                             }
                         },
                     },
                     // Payload is a nested OscMessage
-                    MsgArgsType::Struct => match variant_props.address {
-                        OscBranchFmt::Str(component_name) => quote! {
-                            if component_name == #component_name {
-                                return Ok(#typename::#variant_ident((), osc_address::OscMessage::deserialize_body(downstream_address, seq)?));
+                    MsgArgsType::Struct => match *variant_address {
+                        // The literal may span several components, so match
+                        // it against the address directly.
+                        OscBranchFmt::Str(ref literal) => {
+                            let literal_address = format!("/{}", literal);
+                            quote! {
+                                if let Some(downstream_address) = address.strip_prefix(#literal_address) {
+                                    if downstream_address.is_empty() || downstream_address.starts_with('/') {
+                                        return Ok(#construct_literal);
+                                    }
+                                }
+                            }
+                        },
+                        OscBranchFmt::Fmt(ref template) => {
+                            let parse_path_arg = parse_template(template, &variant_props.path_args_type, &variant_props.parse_with, quote! { osc_address::match_template }, quote! { address });
+                            quote! {
+                                if let Some((path_arg, downstream_address)) = #parse_path_arg {
+                                    return Ok(#construct);
+                                }
                             }
                         },
-                        OscBranchFmt::None => quote! {
-                            // if we can parse the path argument, then the address variant is matched
-                            if let Ok(path_arg) = component_name.parse() {
-                                return Ok(#typename::#variant_ident(path_arg, osc_address::OscMessage::deserialize_body(downstream_address, seq)?));
+                        OscBranchFmt::None => {
+                            let parse_path_arg = parse_path_arg(quote! { component_name }, &variant_props.path_args_type, &variant_props.parse_with);
+                            quote! {
+                                // if we can parse the path argument, then the address variant is matched
+                                if let Ok(path_arg) = #parse_path_arg {
+                                    return Ok(#construct);
+                                }
                             }
                         },
                     }
+                }).collect::<Vec<_>>();
+                quote! {
+                    #(#address_arms)*
                 }
-            });
-            quote! {
-                // split the address at the next "/":
-                // start from idx=1 because the address begins with "/<component_name>/<downstream ...>"
-                let slash_idx = address[1..].find('/');
-                let downstream_address = match slash_idx {
-                    None => String::new(),
-                    Some(idx) => address.split_off(1+idx),
+            };
+            let arms = variants.iter().filter(|variant| !get_variant_props(variant, &container).fallback).map(&variant_arm);
+            // A variant which captures the whole address also captures the root address.
+            let root_arms = &variants.iter().filter(|variant| get_variant_props(variant, &container).captures_whole_address()).map(&variant_arm).collect::<Vec<_>>();
+            // split the address at the next "/":
+            // start from idx=1 because the address begins with "/<component_name>/<downstream ...>"
+            // Both halves borrow from the address, so routing never allocates.
+            let split_address = quote! {
+                let (component_name, downstream_address) = match address[1..].find('/') {
+                    None => (&address[1..], ""),
+                    Some(idx) => (&address[1..1+idx], &address[1+idx..]),
                 };
-                let component_name = &address[1..];
-                #(#arms)*
-                // If no patterns matched, then:
-                return Err(serde::de::Error::invalid_value(serde::de::Unexpected::Str(component_name), &"an OSC component name that matches one of the enum variants"));
+            };
+            let root_fallthrough = if root_arms.is_empty() {
+                quote! {}
+            } else {
+                quote! {
+                    if address.len() <= 1 {
+                        #(#root_arms)*
+                    }
+                }
+            };
+            match get_fallback(variants, &container) {
+                Some(fallback_ident) => quote! {
+                    if address.len() > 1 {
+                        #split_address
+                        #(#arms)*
+                    }
+                    #root_fallthrough
+                    // If no patterns matched, then the fallback variant
+                    // captures the remainder of the address.
+                    let remainder = if address.is_empty() { "/" } else { address };
+                    let path_arg = remainder.parse().map_err(|_| serde::de::Error::invalid_value(serde::de::Unexpected::Str(remainder), &"the remainder of an OSC address"))?;
+                    match seq.next_element()? {
+                        None => Err(serde::de::Error::invalid_length(0, &"a sequence representing an OSC message payload")),
+                        Some(msg_data) => Ok(#typename::#fallback_ident(path_arg, msg_data)),
+                    }
+                },
+                None => quote! {
+                    if address.len() <= 1 {
+                        #(#root_arms)*
+                        return Err(serde::de::Error::invalid_value(serde::de::Unexpected::Str(address), &"an OSC address with a component that matches one of the enum variants"));
+                    }
+                    #split_address
+                    #(#arms)*
+                    // If no patterns matched, then:
+                    return Err(serde::de::Error::invalid_value(serde::de::Unexpected::Str(component_name), &"an OSC component name that matches one of the enum variants"));
+                },
             }
         },
         syn::Body::Struct(ref _variant_data) => quote! {
             if address != "" && address != "/" {
-                return Err(serde::de::Error::invalid_value(serde::de::Unexpected::Str(&address), &"the OSC path to be terminated by this point"));
+                return Err(serde::de::Error::invalid_value(serde::de::Unexpected::Str(address), &"the OSC path to be terminated by this point"));
             }
             let me = seq.next_element()?;
             match me {
@@ -394,9 +980,250 @@ fn impl_osc_address(ast: &MacroInput) -> quote::Tokens {
     };
 
 
+    let match_pattern_impl = match ast.body {
+        syn::Body::Enum(ref variants) => {
+            // create a series of:
+            // if component_pattern.matches("<variant_address>") {
+            //     matches.push(#typename::#variant_ident((), args.replay()?))
+            // }
+            // // ...
+            // where a failure to decode the payload only disqualifies that one variant.
+            let variant_arm = |variant: &syn::Variant| {
+                let variant_ident = variant.ident.clone();
+                let variant_props = get_variant_props(variant, &container);
+                // Expression yielding Some((path_arg, downstream_pattern)) if the
+                // leading components of the pattern select the variant via the
+                // given address.
+                let address_matched = |variant_address: &OscBranchFmt| match (variant_address, &variant_props.path_args_type) {
+                    (OscBranchFmt::Str(literal), _) => {
+                        let components = literal.split('/').collect::<Vec<_>>();
+                        let num_components = components.len();
+                        let indices = 0..num_components;
+                        quote! {
+                            if pattern.len() >= #num_components #(&& pattern[#indices].matches(#components))* {
+                                Some(((), &pattern[#num_components..]))
+                            } else {
+                                None
+                            }
+                        }
+                    },
+                    // Each candidate for the component, as enumerated below,
+                    // must parse as the path argument.
+                    (OscBranchFmt::None, PathArgsType::One(_)) => {
+                        let parse_path_arg = parse_path_arg(quote! { path_arg_text }, &variant_props.path_args_type, &variant_props.parse_with);
+                        quote! {
+                            match #parse_path_arg {
+                                Ok(path_arg) => Some((path_arg, downstream_pattern)),
+                                Err(_) => None,
+                            }
+                        }
+                    },
+                    (OscBranchFmt::Fmt(template), PathArgsType::One(_)) => {
+                        parse_template(template, &variant_props.path_args_type, &variant_props.parse_with, quote! { osc_address::match_pattern_template }, quote! { pattern })
+                    },
+                    // The variant captures the whole pattern.
+                    (OscBranchFmt::None, PathArgsType::Unit) => quote! {
+                        Some(((), pattern))
+                    },
+                    (OscBranchFmt::Fmt(_), PathArgsType::Unit) => unreachable!("rejected by get_variant_props"),
+                };
+                // If several of the variant's addresses match, the first one
+                // selects it; the variant is never matched twice.
+                let mut addresses = variant_props.addresses();
+                let first_matched = address_matched(addresses.next().unwrap());
+                let matched = addresses.fold(first_matched, |matched, variant_address| {
+                    let alias_matched = address_matched(variant_address);
+                    quote! {
+                        (#matched).or_else(|| #alias_matched)
+                    }
+                });
+                let construct = construct_variant(typename, &variant_ident, &variant_props.shape, quote! { path_arg }, quote! { msg_data });
+                let arm = match variant_props.msg_args_type {
+                    // Whatever follows the variant's own components is captured
+                    // verbatim, e.g. "/foo/*", and the payload is the argument list.
+                    MsgArgsType::Seq if variant_props.rest.is_some() && !variant_props.borrowed.is_empty() => quote! {
+                        if (#matched).is_some() {
+                            error = Some(serde::de::Error::custom("a message with borrowed arguments cannot be matched by an OSC address pattern"));
+                        }
+                    },
+                    MsgArgsType::Seq if variant_props.rest.is_some() => {
+                        let rest_ty = &variant_props.rest;
+                        let construct = construct_variant(typename, &variant_ident, &variant_props.shape, quote! { (path_arg, rest) }, quote! { msg_data });
+                        quote! {
+                            if let Some((path_arg, downstream_pattern)) = #matched {
+                                let rest_address = osc_address::pattern_address(downstream_pattern);
+                                if let Ok(rest) = rest_address.parse::<#rest_ty>() {
+                                    match args.replay() {
+                                        Ok(msg_data) => matches.push(#construct),
+                                        Err(e) => error = Some(e),
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    // Payload IS the message data; not a nested OscMessage
+                    // By necessity this is the leaf message, so the pattern
+                    // must not extend any further.
+                    // The replayed arguments don't outlive this call, so they
+                    // can't be lent to the message.
+                    MsgArgsType::Seq if !variant_props.borrowed.is_empty() => quote! {
+                        if let Some((_, downstream_pattern)) = #matched {
+                            if downstream_pattern.is_empty() {
+                                error = Some(serde::de::Error::custom("a message with borrowed arguments cannot be matched by an OSC address pattern"));
+                            }
+                        }
+                    },
+                    MsgArgsType::Seq => quote! {
+                        if let Some((path_arg, downstream_pattern)) = #matched {
+                            if downstream_pattern.is_empty() {
+                                match args.replay() {
+                                    Ok(msg_data) => matches.push(#construct),
+                                    Err(e) => error = Some(e),
+                                }
+                            }
+                        }
+                    },
+                    // Payload is a nested OscMessage
+                    // The path argument is parsed anew for each match, so it needn't be Clone.
+                    MsgArgsType::Struct => quote! {
+                        if let Some((_, downstream_pattern)) = #matched {
+                            match osc_address::OscMessage::match_pattern(downstream_pattern, args) {
+                                Ok(sub_matches) => for msg_data in sub_matches {
+                                    let (path_arg, _) = (#matched).unwrap();
+                                    matches.push(#construct);
+                                },
+                                Err(e) => error = Some(e),
+                            }
+                        }
+                    },
+                };
+                match (&variant_props.address, &variant_props.path_args_type) {
+                    // A path argument implementing OscPathArg is matched against
+                    // each of its values, whereas others can't be enumerated, so
+                    // the raw pattern component must itself parse as the path argument.
+                    (OscBranchFmt::None, PathArgsType::One(path_arg_ty)) => {
+                        let names = match variant_props.parse_with {
+                            // The names needn't parse with a custom function.
+                            Some(_) => quote! { None },
+                            None => quote! { (&&osc_address::PathArgNames::<#path_arg_ty>::new()).names() },
+                        };
+                        quote! {
+                            {
+                                #[allow(unused_imports)]
+                                use osc_address::{EnumeratedPathArg, OpaquePathArg};
+                                for path_arg_text in osc_address::path_arg_candidates(component_pattern, #names) {
+                                    #arm
+                                }
+                            }
+                        }
+                    },
+                    _ => arm,
+                }
+            };
+            let arms = variants.iter().filter(|variant| !get_variant_props(variant, &container).fallback).map(&variant_arm);
+            // A variant which captures the whole pattern also captures the empty pattern.
+            let root_arms = &variants.iter().filter(|variant| get_variant_props(variant, &container).captures_whole_address()).map(&variant_arm).collect::<Vec<_>>();
+            let root_fallthrough = if root_arms.is_empty() {
+                quote! {}
+            } else {
+                quote! {
+                    if pattern.is_empty() {
+                        #(#root_arms)*
+                    }
+                }
+            };
+            match get_fallback(variants, &container) {
+                Some(fallback_ident) => quote! {
+                    let mut matches = Vec::new();
+                    let mut error = None;
+                    if let Some((component_pattern, downstream_pattern)) = pattern.split_first() {
+                        #(#arms)*
+                    }
+                    #root_fallthrough
+                    if matches.is_empty() {
+                        // The fallback variant captures the remainder of the pattern.
+                        let remainder = osc_address::pattern_address(pattern);
+                        match remainder.parse() {
+                            Ok(path_arg) => return args.replay().map(|msg_data| vec![#typename::#fallback_ident(path_arg, msg_data)]),
+                            Err(_) => return Err(error.unwrap_or_else(|| serde::de::Error::invalid_value(serde::de::Unexpected::Str(&remainder), &"the remainder of an OSC address pattern"))),
+                        }
+                    }
+                    Ok(matches)
+                },
+                None => quote! {
+                    let mut matches = Vec::new();
+                    let mut error = None;
+                    let (component_pattern, downstream_pattern) = match pattern.split_first() {
+                        None => {
+                            #(#root_arms)*
+                            if matches.is_empty() {
+                                return Err(error.unwrap_or_else(|| serde::de::Error::invalid_value(serde::de::Unexpected::Str(""), &"an OSC address pattern that matches one of the enum variants")));
+                            }
+                            return Ok(matches);
+                        },
+                        Some(split) => split,
+                    };
+                    #(#arms)*
+                    if matches.is_empty() {
+                        // Report why the last candidate variant was rejected, if any.
+                        return Err(error.unwrap_or_else(|| serde::de::Error::invalid_value(serde::de::Unexpected::Str(component_pattern.as_str()), &"an OSC address pattern that matches one of the enum variants")));
+                    }
+                    Ok(matches)
+                },
+            }
+        },
+        syn::Body::Struct(ref _variant_data) if !ast.generics.lifetimes.is_empty() => quote! {
+            if let Some(component_pattern) = pattern.first() {
+                return Err(serde::de::Error::invalid_value(serde::de::Unexpected::Str(component_pattern.as_str()), &"the OSC path to be terminated by this point"));
+            }
+            Err(serde::de::Error::custom("a message with borrowed arguments cannot be matched by an OSC address pattern"))
+        },
+        syn::Body::Struct(ref _variant_data) => quote! {
+            if let Some(component_pattern) = pattern.first() {
+                return Err(serde::de::Error::invalid_value(serde::de::Unexpected::Str(component_pattern.as_str()), &"the OSC path to be terminated by this point"));
+            }
+            Ok(vec![args.replay()?])
+        },
+    };
+
+
+    // All impls are generic over the lifetime of the deserialized data, which
+    // must outlive whatever the message arguments borrow from it.
+    let mut generics = ast.generics.clone();
+    let mut de_lifetime = syn::LifetimeDef::new("'de");
+    de_lifetime.bounds = match ast.body {
+        syn::Body::Enum(ref variants) => {
+            let mut borrowed = variants.iter().flat_map(|variant| get_variant_props(variant, &container).borrowed).collect::<Vec<_>>();
+            borrowed.retain(|lifetime| ast.generics.lifetimes.iter().any(|def| def.lifetime == *lifetime));
+            borrowed.sort_by(|a, b| a.ident.as_ref().cmp(b.ident.as_ref()));
+            borrowed.dedup();
+            borrowed
+        },
+        // The payload struct's own Deserialize impl may borrow for any of its lifetimes.
+        syn::Body::Struct(_) => ast.generics.lifetimes.iter().map(|def| def.lifetime.clone()).collect(),
+    };
+    generics.lifetimes.insert(0, de_lifetime);
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+    let predicates = &ast.generics.where_clause.predicates;
+    let bounds = match ast.body {
+        syn::Body::Enum(ref variants) => variants.iter().flat_map(|variant| get_variant_bounds(variant, &container, &ast.generics)).collect(),
+        // The payload struct is (de)serialized through its own Serialize/Deserialize impls.
+        syn::Body::Struct(_) if !ast.generics.lifetimes.is_empty() => vec![quote! {
+            #typename #ty_generics: serde::Serialize + serde::Deserialize<'de>
+        }],
+        syn::Body::Struct(_) if !ast.generics.ty_params.is_empty() => vec![quote! {
+            #typename #ty_generics: serde::Serialize + serde::de::DeserializeOwned
+        }],
+        syn::Body::Struct(_) => Vec::new(),
+    };
+    let where_clause = quote! {
+        where #(#predicates,)* #(#bounds,)*
+    };
+
     let serialize_impl = if do_impl_serde {
         quote! {
-            impl serde::Serialize for #typename {
+            impl #impl_generics serde::Serialize for #typename #ty_generics #where_clause {
                 fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
                     // serialization is a two-step process.
                     // 1: Serialize the message address.
@@ -404,7 +1231,8 @@ fn impl_osc_address(ast: &MacroInput) -> quote::Tokens {
                     //    recurse through multiple OscMessage instances to
                     //    locate the leaf payload.
                     let mut tup = serializer.serialize_tuple(2)?;
-                    serde::ser::SerializeTuple::serialize_element(&mut tup, &osc_address::OscMessage::get_address(self))?;
+                    let address = osc_address::OscMessage::get_address(self).map_err(serde::ser::Error::custom)?;
+                    serde::ser::SerializeTuple::serialize_element(&mut tup, &address)?;
                     // Now serialize the message payload
                     osc_address::OscMessage::serialize_body(self, &mut tup)?;
                     serde::ser::SerializeTuple::end(tup)
@@ -417,28 +1245,29 @@ fn impl_osc_address(ast: &MacroInput) -> quote::Tokens {
 
     let deserialize_impl = if do_impl_serde {
         quote! {
-            impl<'de> serde::Deserialize<'de> for #typename {
+            impl #impl_generics serde::Deserialize<'de> for #typename #ty_generics #where_clause {
                 fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
                     where D: serde::Deserializer<'de>
                 {
-                    deserializer.deserialize_seq(ToplevelVisitor)
+                    deserializer.deserialize_seq(ToplevelVisitor(std::marker::PhantomData))
                 }
             }
-            struct ToplevelVisitor;
-            impl<'de> serde::de::Visitor<'de> for ToplevelVisitor {
-                type Value = #typename;
+            // Generic over the message type, so as not to repeat its generics.
+            struct ToplevelVisitor<M>(std::marker::PhantomData<M>);
+            impl<'de, M: osc_address::OscMessage<'de>> serde::de::Visitor<'de> for ToplevelVisitor<M> {
+                type Value = M;
                 fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
                     formatter.write_str("a tuple of (String, (msg_args ...))")
                 }
                 fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
                     where A: serde::de::SeqAccess<'de>
                 {
-                    let address: Option<String> = seq.next_element()?;
+                    let address: Option<osc_address::BorrowedAddress<'de>> = seq.next_element()?;
                     let address = match address {
                         None => Err(serde::de::Error::invalid_length(0, &"an OSC address string, followed by a sequence of message arguments")),
                         Some(addr) => Ok(addr),
                     }?;
-                    osc_address::OscMessage::deserialize_body(address, seq)
+                    osc_address::OscMessage::deserialize_body(address.as_str(), seq)
                 }
             }
         }
@@ -456,19 +1285,23 @@ fn impl_osc_address(ast: &MacroInput) -> quote::Tokens {
             extern crate serde;
             extern crate osc_address;
             use std;
-            impl<'de> osc_address::OscMessage<'de> for #typename {
+            impl #impl_generics osc_address::OscMessage<'de> for #typename #ty_generics #where_clause {
                 // not all branches will use path_arg or msg_data vars decoded in the enum cases.
                 #[allow(unused_mut, unused_variables)]
-                fn build_address(&self, address: &mut String) {
+                fn build_address<W: std::fmt::Write>(&self, address: &mut osc_address::OscAddressWriter<W>) -> Result<(), osc_address::OscAddressError> {
                     #build_address_impl
                 }
                 fn serialize_body<S: serde::ser::SerializeTuple>(&self, serializer: &mut S) -> Result<(), S::Error> {
                     #serialize_body_impl
                 }
-                #[allow(unused_mut)]
-                fn deserialize_body<D: serde::de::SeqAccess<'de>>(mut address: String, mut seq: D) -> Result<#typename, D::Error> {
+                #[allow(unused_mut, unused_variables)]
+                fn deserialize_body<D: serde::de::SeqAccess<'de>>(address: &str, mut seq: D) -> Result<Self, D::Error> {
                     #deserialize_body_impl
                 }
+                #[allow(unused_mut, unused_variables)]
+                fn match_pattern<E: serde::de::Error>(pattern: &[osc_address::OscComponentPattern], args: &osc_address::ArgBuffer) -> Result<Vec<Self>, E> {
+                    #match_pattern_impl
+                }
             }
             #serialize_impl
             #deserialize_impl
@@ -478,71 +1311,1032 @@ fn impl_osc_address(ast: &MacroInput) -> quote::Tokens {
 
 
 
-/// Return all the configuration data associated with a given enum variant.
-fn get_variant_props(variant: &syn::Variant) -> OscRouteProperties {
+/// Create `FromStr`, `Display` and `OscPathArg` implementations for a C-like enum.
+/// 
+/// This is never called directly, but invoked by preceding the type
+/// definition with `[derive(OscPathArg)]`.
+#[proc_macro_derive(OscPathArg, attributes(osc_address))]
+pub fn derive_osc_path_arg(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_derive_input(&input.to_string()).unwrap();
+    let names = match get_path_arg_names(&ast) {
+        Ok(names) => names,
+        Err(errors) => return errors.into_iter().flat_map(|(target, message)| compile_error(&message, error_span(&input, &target))).collect(),
+    };
+    impl_osc_path_arg(&ast, &names).parse().unwrap()
+}
+
+fn impl_osc_path_arg(ast: &MacroInput, names: &[(syn::Ident, String)]) -> quote::Tokens {
+    let typename = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let variants = &names.iter().map(|(ident, _)| quote! { #typename::#ident }).collect::<Vec<_>>();
+    let names = &names.iter().map(|(_, name)| name).collect::<Vec<_>>();
+    let dummy_const = syn::Ident::new(format!("_IMPL_OSCPATHARG_FOR_{}", typename));
+    quote! {
+        #[allow(non_upper_case_globals)]
+        const #dummy_const: () = {
+            extern crate osc_address;
+            use std;
+            impl #impl_generics std::str::FromStr for #typename #ty_generics #where_clause {
+                type Err = osc_address::OscPathArgError;
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    match s {
+                        #(#names => Ok(#variants),)*
+                        _ => Err(osc_address::OscPathArgError::new(s)),
+                    }
+                }
+            }
+            impl #impl_generics std::fmt::Display for #typename #ty_generics #where_clause {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    f.pad(match *self {
+                        #(#variants => #names,)*
+                    })
+                }
+            }
+            impl #impl_generics osc_address::OscPathArg for #typename #ty_generics #where_clause {
+                const NAMES: &'static [&'static str] = &[#(#names),*];
+            }
+        };
+    }
+}
+
+/// Return each variant of a C-like enum deriving OscPathArg alongside its
+/// spelling within an address, or every error in its definition.
+fn get_path_arg_names(ast: &MacroInput) -> Result<Vec<(syn::Ident, String)>, InputErrors> {
+    let mut errors = Vec::new();
+    let container = parse_container_props(ast).unwrap_or_else(|message| {
+        errors.push((ErrorTarget::Container, message));
+        ContainerProperties::default()
+    });
+    let variants = match ast.body {
+        syn::Body::Enum(ref variants) => variants,
+        syn::Body::Struct(_) => {
+            errors.push((ErrorTarget::Container, "#[derive(OscPathArg)] only applies to C-like enums, whose variants are the values of the path argument".to_owned()));
+            return Err(errors);
+        },
+    };
+    let mut names: Vec<(syn::Ident, String)> = Vec::new();
+    for variant in variants {
+        match get_path_arg_name(variant, &container) {
+            Ok(ref name) if names.iter().any(|(_, other)| other == name) => {
+                errors.push((ErrorTarget::Variant(variant.ident.clone()), format!("The name \"{}\" is already taken by another variant", name)));
+            },
+            Ok(name) => names.push((variant.ident.clone(), name)),
+            Err(variant_errors) => errors.extend(variant_errors),
+        }
+    }
+    if errors.is_empty() {
+        Ok(names)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Return the spelling of a variant of an enum deriving OscPathArg within an
+/// address, or describe every error in its definition.
+fn get_path_arg_name(variant: &syn::Variant, container: &ContainerProperties) -> Result<String, InputErrors> {
+    let variant_error = |message: String| vec![(ErrorTarget::Variant(variant.ident.clone()), message)];
+    if variant.data != syn::VariantData::Unit {
+        return Err(variant_error("The variants of an enum deriving OscPathArg must be unit variants, e.g. `Filter`".to_owned()));
+    }
+    let mut errors = Vec::new();
+    let mut rename = None;
+    for (attr, item) in get_osc_attr_items(&variant.attrs) {
+        match *item {
+            NestedMetaItem::MetaItem(MetaItem::NameValue(ref name, syn::Lit::Str(ref value, _))) if name == "rename" => rename = Some(value.clone()),
+            _ => errors.push((ErrorTarget::Attribute(variant.ident.clone(), attr), format!("Unsupported directive #[osc_address({})] on a variant of an enum deriving OscPathArg; expected `rename = \"...\"`", quote! { #item }))),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let name = match (rename, container.rename_all) {
+        (Some(name), _) => name,
+        (None, Some(rule)) => rule.apply(variant.ident.as_ref()),
+        (None, None) => variant.ident.as_ref().to_owned(),
+    };
+    if name.is_empty() || name.contains(|c| FORBIDDEN_CHARS.contains(c)) {
+        return Err(variant_error(format!("\"{}\" is not a valid OSC address component: it must be non-empty and may not contain any of \"{}\"", name, FORBIDDEN_CHARS)));
+    }
+    Ok(name)
+}
+
+/// Return all the configuration data associated with a given enum variant,
+/// which `check_input` has already verified.
+fn get_variant_props(variant: &syn::Variant, container: &ContainerProperties) -> OscRouteProperties {
+    parse_variant_props(variant, container).unwrap_or_else(|_| unreachable!("rejected by check_input"))
+}
+
+/// Parse all the configuration data associated with a given enum variant,
+/// or describe every error in its directives. Errors in the directives
+/// themselves are all reported together, each against its attribute.
+fn parse_variant_props(variant: &syn::Variant, container: &ContainerProperties) -> Result<OscRouteProperties, InputErrors> {
+    // Report an error against the given #[osc_address(..)] attribute of the variant, or the variant itself.
+    let error_at = |attr: Option<usize>, message: String| {
+        let target = match attr {
+            Some(attr) => ErrorTarget::Attribute(variant.ident.clone(), attr),
+            None => ErrorTarget::Variant(variant.ident.clone()),
+        };
+        vec![(target, message)]
+    };
+    let mut errors = Vec::new();
+    // Each directive is kept alongside the index of the attribute which holds it.
     let mut addresses = Vec::new();
+    let mut aliases = Vec::new();
+    let mut fallback_attr = None;
+    let mut allow_overlap = false;
+    let mut payload = None;
+    let mut payload_attr = None;
+    let mut parse_with = None;
+    let mut format_with = None;
+    let mut with = None;
+    let mut rest = None;
     // Iter all X in #[osc_address X]
-    for item in get_osc_meta_items(variant) {
-        match *item {
-            NestedMetaItem::MetaItem(ref item) => match *item {
-                MetaItem::NameValue(ref name, ref lit) => if name == "address" {
-                    addresses.push(OscBranchFmt::new(lit));
-                },
-                _ => panic!("Unsupported #[osc_address] directive: {:?}", item),
+    for (attr, item) in get_osc_attr_items(&variant.attrs) {
+        let parsed = match *item {
+            NestedMetaItem::MetaItem(MetaItem::NameValue(ref name, ref lit)) if name == "alias" => OscBranchFmt::new(lit).map(|alias| aliases.push((attr, alias))),
+            NestedMetaItem::MetaItem(MetaItem::NameValue(ref name, ref lit)) if name == "address" => OscBranchFmt::new(lit).map(|address| addresses.push((attr, address))),
+            NestedMetaItem::MetaItem(MetaItem::Word(ref name)) if name == "fallback" => {
+                fallback_attr = Some(attr);
+                Ok(())
+            },
+            NestedMetaItem::MetaItem(MetaItem::Word(ref name)) if name == "allow_overlap" => {
+                allow_overlap = true;
+                Ok(())
+            },
+            NestedMetaItem::MetaItem(MetaItem::Word(ref name)) if name == "rest" => {
+                Err("#[osc_address(rest)] marks the field which captures the remainder of the address, e.g. Forward(#[osc_address(rest)] OscAddress, OscRawArgs), rather than the variant".to_owned())
             },
-            _ => panic!("Unsupported #[osc_address] directive: {:?}", item),
+            NestedMetaItem::MetaItem(MetaItem::NameValue(ref name, syn::Lit::Str(ref kind, _))) if name == "payload" => {
+                if payload_attr.is_some() {
+                    Err("Expected no more than one #[osc_address(payload=...)] for each enum variant".to_owned())
+                } else {
+                    payload_attr = Some(attr);
+                    PayloadKind::new(kind).map(|kind| payload = Some(kind))
+                }
+            },
+            NestedMetaItem::MetaItem(MetaItem::NameValue(ref name, ref lit)) if name == "parse_with" => parse_fn_path(name, lit).map(|path| parse_with = Some(path)),
+            NestedMetaItem::MetaItem(MetaItem::NameValue(ref name, ref lit)) if name == "format_with" => parse_fn_path(name, lit).map(|path| format_with = Some(path)),
+            NestedMetaItem::MetaItem(MetaItem::NameValue(ref name, ref lit)) if name == "with" => parse_fn_path(name, lit).map(|path| with = Some((attr, path))),
+            _ => Err(format!("Unsupported directive #[osc_address({})] on an enum variant; expected `address = \"...\"`, `alias = \"...\"`, `payload = \"...\"`, `parse_with = \"...\"`, `format_with = \"...\"`, `with = \"...\"`, `fallback` or `allow_overlap`", quote! { #item })),
+        };
+        if let Err(message) = parsed {
+            errors.extend(error_at(Some(attr), message));
         }
     }
-    let (path_args_type, msg_args_type) = match variant.data {
+    // The checks below rely on the directives, so they're only made once all of them are valid.
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let fallback = fallback_attr.is_some();
+    let (path_args_type, msg_args_type, shape) = match variant.data {
+        syn::VariantData::Unit => {
+            if payload.is_some() {
+                return Err(error_at(payload_attr, "A unit variant has no payload, so it may not have an #[osc_address(payload=...)]".to_owned()));
+            }
+            (PathArgsType::Unit, MsgArgsType::Seq, VariantShape::Unit)
+        },
+        syn::VariantData::Tuple(ref fields) if fields.iter().any(is_rest_field) => {
+            if fields.len() != 2 || !is_rest_field(&fields[0]) || is_rest_field(&fields[1]) {
+                return Err(error_at(None, "A tuple variant captures the remainder of the address in its first entry and the message payload in its second, e.g. Forward(#[osc_address(rest)] OscAddress, OscRawArgs)".to_owned()));
+            }
+            for field in fields {
+                check_field_attrs(field).map_err(|message| error_at(None, message))?;
+            }
+            if payload == Some(PayloadKind::Message) {
+                return Err(error_at(payload_attr, "An #[osc_address(rest)] variant consumes the whole address, so its payload is always its argument list and may not be a nested OscMessage".to_owned()));
+            }
+            rest = Some(fields[0].ty.clone());
+            // The payload is the raw argument list, not a nested OscMessage.
+            (PathArgsType::Unit, MsgArgsType::Seq, VariantShape::Rest)
+        },
+        syn::VariantData::Tuple(ref fields) if fields.len() == 1 => {
+            let msg_args_type = get_msg_args_type(&fields[0].ty, payload).map_err(|message| error_at(payload_attr, message))?;
+            (PathArgsType::Unit, msg_args_type, VariantShape::Newtype)
+        },
         syn::VariantData::Tuple(ref fields) => {
             if fields.len() != 2 {
-                panic!("Expected OscMessage enum variant tuple to have exactly two entries: one for path arguments and one for the message payload. Got: {:?}", fields);
+                return Err(error_at(None, format!("Expected OscMessage enum variant tuple to have either one entry for the message payload, or exactly two entries: one for path arguments and one for the message payload. Found {} entries", fields.len())));
             }
             let path_args_type = match fields[0].ty {
-                Ty::Tup(ref v) if v.len() == 0 => PathArgsType::Unit,
-                _ => PathArgsType::One,
+                Ty::Tup(ref v) if v.is_empty() => PathArgsType::Unit,
+                ref ty => PathArgsType::One(ty.clone()),
             };
-            let msg_args_type = match fields[1].ty {
-                // Is the message data a sequence type, or a nested OscMessage?
-                Ty::Slice(_) | Ty::Array(_, _) | Ty::Tup(_) => MsgArgsType::Seq,
-                _ => MsgArgsType::Struct,
+            let msg_args_type = get_msg_args_type(&fields[1].ty, payload).map_err(|message| error_at(payload_attr, message))?;
+            (path_args_type, msg_args_type, VariantShape::Tuple)
+        },
+        syn::VariantData::Struct(ref fields) => {
+            if payload.is_some() {
+                return Err(error_at(payload_attr, "The fields of a struct-like variant are always its message arguments, so it may not have an #[osc_address(payload=...)]".to_owned()));
+            }
+            for field in fields {
+                check_field_attrs(field).map_err(|message| error_at(None, message))?;
+            }
+            let (rest_fields, fields): (Vec<_>, Vec<_>) = fields.iter().partition(|field| is_rest_field(field));
+            if rest_fields.len() > 1 {
+                return Err(error_at(None, format!("Expected no more than one #[osc_address(rest)] field for each enum variant, but found {}", rest_fields.len())));
+            }
+            rest = rest_fields.first().map(|field| field.ty.clone());
+            let (path_fields, arg_fields): (Vec<_>, Vec<_>) = fields.into_iter().partition(|field| is_path_field(field));
+            let mut path_tys = path_fields.iter().map(|field| field.ty.clone()).collect::<Vec<_>>();
+            let path_args_type = match path_tys.len() {
+                0 => PathArgsType::Unit,
+                1 => PathArgsType::One(path_tys.remove(0)),
+                // Several path fields bind to the placeholders of the address, in order.
+                _ => PathArgsType::One(Ty::Tup(path_tys)),
             };
-            (path_args_type, msg_args_type)
+            let field_idents = |fields: Vec<&syn::Field>| fields.into_iter().map(|field| field.ident.clone().unwrap()).collect();
+            let rest_field = rest_fields.first().map(|field| field.ident.clone().unwrap());
+            let shape = VariantShape::Struct{ path_fields: field_idents(path_fields), rest_field, arg_fields: field_idents(arg_fields) };
+            // The remaining fields are the message arguments, so this is always a leaf.
+            (path_args_type, MsgArgsType::Seq, shape)
         },
-        _ => panic!("Expected OscMessage enum variant to be a tuple. Got: {:?}", variant.data),
     };
+    // A `with` module provides both functions, as `module::parse` and `module::format`.
+    if let Some((with_attr, module)) = with {
+        if parse_with.is_some() || format_with.is_some() {
+            return Err(error_at(Some(with_attr), "#[osc_address(with=...)] provides both the parse and format functions, so it may not be combined with #[osc_address(parse_with=...)] or #[osc_address(format_with=...)]".to_owned()));
+        }
+        let function = |name: &str| {
+            let mut path = module.clone();
+            path.segments.push(syn::PathSegment::from(name));
+            path
+        };
+        parse_with = Some(function("parse"));
+        format_with = Some(function("format"));
+    }
+    let mut borrowed = Vec::new();
+    if let MsgArgsType::Seq = msg_args_type {
+        for ty in get_msg_tys(variant) {
+            borrowed_lifetimes(&ty, &mut borrowed);
+        }
+    }
     // Decode the address
-    let address = if addresses.len() > 1 {
-        panic!("Expected no more than one #[osc_address(address=...)] for each enum variant. Saw: {:?}", addresses);
+    let (address_attr, address) = if addresses.len() > 1 {
+        return Err(error_at(Some(addresses[1].0), format!("Expected no more than one #[osc_address(address=...)] for each enum variant, but found {}", addresses.len())));
     } else if addresses.len() == 1 {
-        addresses.into_iter().next().unwrap()
+        let (attr, address) = addresses.remove(0);
+        (Some(attr), address)
     } else {
-        OscBranchFmt::None
+        match container.rename_all {
+            // Variants without path arguments take their address from their name.
+            // A variant capturing the remainder of the address has none of its own.
+            Some(rule) if path_args_type == PathArgsType::Unit && !fallback && rest.is_none() => {
+                (None, OscBranchFmt::Str(rule.apply(variant.ident.as_ref())))
+            },
+            _ => (None, OscBranchFmt::None),
+        }
     };
     // Verify illegal attribute combinations
-    if let OscBranchFmt::Str(_) = address {
-        if path_args_type != PathArgsType::Unit {
-            panic!("A #[osc_address(address=\"<literal>\")] directive implies no path arguments, but both were found");
+    if fallback {
+        if let OscBranchFmt::Str(_) | OscBranchFmt::Fmt(_) = address {
+            return Err(error_at(address_attr.or(fallback_attr), "A #[osc_address(fallback)] variant captures the remainder of the address, so it may not also have an #[osc_address(address=...)]".to_owned()));
+        }
+        if rest.is_some() {
+            return Err(error_at(fallback_attr, "A #[osc_address(fallback)] variant already captures the remainder of the address, so it may not have an #[osc_address(rest)] field".to_owned()));
+        }
+        if path_args_type == PathArgsType::Unit || shape != VariantShape::Tuple {
+            return Err(error_at(fallback_attr, "A #[osc_address(fallback)] variant must capture the remainder of the address, e.g. Unknown(String, OscRawArgs)".to_owned()));
+        }
+        if let Some(&(alias_attr, _)) = aliases.first() {
+            return Err(error_at(Some(alias_attr), "A #[osc_address(fallback)] variant captures the remainder of the address, so it may not have an #[osc_address(alias=...)]".to_owned()));
+        }
+        if payload.is_some() && payload != Some(PayloadKind::Args) {
+            return Err(error_at(payload_attr, "The payload of a #[osc_address(fallback)] variant is always its argument list, e.g. OscRawArgs, so only #[osc_address(payload=\"args\")] applies".to_owned()));
+        }
+        if parse_with.is_some() || format_with.is_some() {
+            return Err(error_at(fallback_attr, "A #[osc_address(fallback)] variant captures the remainder of the address via `AsRef<str>` and `FromStr`, so it may not have custom parse or format functions".to_owned()));
+        }
+        // The payload is the raw argument list, not a nested OscMessage.
+        return Ok(OscRouteProperties{ address, aliases: Vec::new(), path_args_type, msg_args_type: MsgArgsType::Seq, fallback, rest, allow_overlap, parse_with, format_with, shape, borrowed });
+    }
+    if address == OscBranchFmt::None && !aliases.is_empty() {
+        return Err(error_at(Some(aliases[0].0), "An #[osc_address(alias=...)] is accepted in place of the variant's address, so the variant must also have an #[osc_address(address=...)]".to_owned()));
+    }
+    let variant_addresses = aliases.iter().map(|&(attr, ref alias)| (Some(attr), alias)).chain(Some((address_attr, &address)));
+    for (attr, variant_address) in variant_addresses {
+        // Without an address, an #[osc_address(rest)] variant captures the whole of it.
+        if rest.is_some() && *variant_address == OscBranchFmt::None && path_args_type == PathArgsType::Unit {
+            continue;
+        }
+        validate_address(variant_address, &path_args_type, &shape).map_err(|message| error_at(attr, message))?;
+        if parse_with.is_some() || format_with.is_some() {
+            validate_path_fns(variant_address, &path_args_type).map_err(|message| error_at(attr, message))?;
+        }
+    }
+    let aliases = aliases.into_iter().map(|(_, alias)| alias).collect();
+    Ok(OscRouteProperties{ address, aliases, path_args_type, msg_args_type, fallback, rest, allow_overlap, parse_with, format_with, shape, borrowed })
+}
+
+/// Verify that the path argument of a variant with custom parse or format
+/// functions is parsed from, and formatted to, a single piece of an address.
+fn validate_path_fns(address: &OscBranchFmt, path_args_type: &PathArgsType) -> Result<(), String> {
+    match (address, path_args_type) {
+        (_, PathArgsType::Unit) => Err("Custom parse and format functions apply to the path argument, but the variant has none".to_owned()),
+        (OscBranchFmt::Fmt(_), PathArgsType::One(ty)) if tuple_len(ty).is_some() => {
+            Err("Custom parse and format functions apply to the path argument as a whole, so it may not be split across several placeholders".to_owned())
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Parse the path to a function, e.g. "track::parse", given in a directive such as `parse_with`.
+fn parse_fn_path(name: &syn::Ident, lit: &syn::Lit) -> Result<syn::Path, String> {
+    match *lit {
+        syn::Lit::Str(ref path, _) => syn::parse_path(path).map_err(|_| format!("Expected a path in #[osc_address({}=...)], e.g. \"my_module::my_fn\", but found \"{}\"", name, path)),
+        _ => Err(format!("Expected a string in #[osc_address({}=...)], e.g. \"my_module::my_fn\"", name)),
+    }
+}
+
+/// Return whether the payload of a tuple or newtype variant is its argument
+/// list or a nested OscMessage, as inferred from its syntax unless given by
+/// #[osc_address(payload=...)].
+fn get_msg_args_type(ty: &Ty, payload: Option<PayloadKind>) -> Result<MsgArgsType, String> {
+    let sequence = matches!(*ty, Ty::Slice(_) | Ty::Array(_, _) | Ty::Tup(_));
+    match payload {
+        None if sequence => Ok(MsgArgsType::Seq),
+        None => Ok(MsgArgsType::Struct),
+        Some(PayloadKind::Args) => Ok(MsgArgsType::Seq),
+        Some(PayloadKind::Message) if sequence => Err("#[osc_address(payload=\"message\")] requires a type implementing OscMessage, but the payload is a tuple, slice or array; use payload=\"args\" for an argument list".to_owned()),
+        Some(PayloadKind::Message) => Ok(MsgArgsType::Struct),
+        Some(PayloadKind::Struct) if sequence => Err("#[osc_address(payload=\"struct\")] requires a struct whose fields are the arguments, but the payload is a tuple, slice or array; use payload=\"args\" instead".to_owned()),
+        Some(PayloadKind::Struct) => Ok(MsgArgsType::Seq),
+    }
+}
+
+/// Verify that an address (or alias) of a variant is consistent with its path arguments.
+fn validate_address(address: &OscBranchFmt, path_args_type: &PathArgsType, shape: &VariantShape) -> Result<(), String> {
+    match *address {
+        OscBranchFmt::Str(_) => if *path_args_type != PathArgsType::Unit {
+            return Err("A #[osc_address(address=\"<literal>\")] directive implies no path arguments, but both were found; add a {} placeholder to the address, or make the path argument ()".to_owned());
+        },
+        OscBranchFmt::Fmt(ref template) => {
+            let placeholders = num_placeholders(template);
+            match *path_args_type {
+                PathArgsType::Unit => return Err("A #[osc_address(address=...)] directive with placeholders requires a path argument, but none was found".to_owned()),
+                PathArgsType::One(ref ty) => match tuple_len(ty) {
+                    Some(len) if len != placeholders => return Err(format!("#[osc_address(address=...)] has {} placeholders, but the path argument is a tuple of {} elements", placeholders, len)),
+                    None if placeholders != 1 => return Err(format!("#[osc_address(address=...)] has {} placeholders, which must bind to a tuple path argument of as many elements", placeholders)),
+                    _ => {},
+                },
+            }
+        },
+        OscBranchFmt::None => match *shape {
+            _ if *path_args_type == PathArgsType::Unit => {
+                return Err("An OscMessage enum variant without path arguments must be annotated with #[osc_address(address=\"<literal>\")]".to_owned());
+            },
+            VariantShape::Struct{ ref path_fields, .. } if path_fields.len() > 1 => {
+                return Err("Several #[osc_address(path)] fields must be bound to the placeholders of an #[osc_address(address=...)]".to_owned());
+            },
+            _ => {},
+        },
+    }
+    Ok(())
+}
+
+/// Return all the configuration data associated with the type deriving
+/// OscMessage, which `check_input` has already verified.
+fn get_container_props(ast: &MacroInput) -> ContainerProperties {
+    parse_container_props(ast).unwrap_or_else(|_| unreachable!("rejected by check_input"))
+}
+
+/// Parse all the configuration data associated with the type deriving
+/// OscMessage, or describe why it is invalid.
+fn parse_container_props(ast: &MacroInput) -> Result<ContainerProperties, String> {
+    let mut rename_all = None;
+    for item in get_osc_meta_items(&ast.attrs) {
+        match *item {
+            NestedMetaItem::MetaItem(MetaItem::NameValue(ref name, syn::Lit::Str(ref rule, _))) if name == "rename_all" => {
+                rename_all = Some(RenameRule::new(rule)?);
+            },
+            _ => return Err(format!("Unsupported directive #[osc_address({})] on a type; expected `rename_all = \"...\"`", quote! { #item })),
+        }
+    }
+    Ok(ContainerProperties{ rename_all })
+}
+
+/// Verify the #[osc_address(..)] attributes of the type deriving OscMessage,
+/// returning each error alongside the item to report it against.
+fn check_input(ast: &MacroInput) -> InputErrors {
+    let mut errors = Vec::new();
+    // Carry on without the container's properties, to report any errors in the variants too.
+    // Variants keep their names as addresses, lest an invalid rename_all be reported
+    // again as every variant lacking an address.
+    let container = parse_container_props(ast).unwrap_or_else(|message| {
+        errors.push((ErrorTarget::Container, message));
+        ContainerProperties{ rename_all: Some(RenameRule::Pascal) }
+    });
+    if let syn::Body::Enum(ref variants) = ast.body {
+        let mut fallbacks = 0;
+        // The valid variants which are tried in order, i.e. all but the fallback.
+        let mut routes: Vec<(&syn::Ident, OscRouteProperties)> = Vec::new();
+        for variant in variants {
+            match parse_variant_props(variant, &container) {
+                Ok(ref variant_props) if variant_props.fallback => {
+                    fallbacks += 1;
+                    if fallbacks > 1 {
+                        errors.push((ErrorTarget::Variant(variant.ident.clone()), "Expected no more than one #[osc_address(fallback)] variant per enum".to_owned()));
+                    }
+                },
+                Ok(variant_props) => {
+                    if !variant_props.allow_overlap {
+                        if let Some(message) = find_overlap(&routes, &variant_props, &ast.generics) {
+                            errors.push((ErrorTarget::Variant(variant.ident.clone()), message));
+                        }
+                    }
+                    routes.push((&variant.ident, variant_props));
+                },
+                Err(variant_errors) => errors.extend(variant_errors),
+            }
+        }
+    }
+    errors
+}
+
+/// Describe how the literal addresses of a variant are shadowed by the
+/// preceding variants, if they are: either because an earlier variant has the
+/// same address, because its path argument parses the address' first component,
+/// because its format-string address matches the address, or because it passes
+/// the remainder of the address below a prefix of it on to a nested message or
+/// an #[osc_address(rest)] field.
+/// A variant which captures the whole address shadows every later variant.
+fn find_overlap(routes: &[(&syn::Ident, OscRouteProperties)], variant_props: &OscRouteProperties, generics: &syn::Generics) -> Option<String> {
+    if let Some(&(ident, _)) = routes.iter().find(|(_, route)| route.captures_whole_address()) {
+        return Some(format!("Every address is captured by the #[osc_address(rest)] variant `{}`, which is tried first; declare this variant before it, or add #[osc_address(allow_overlap)] if this is intended", ident));
+    }
+    let literals = variant_props.addresses().filter_map(|variant_address| match *variant_address {
+        OscBranchFmt::Str(ref literal) => Some(literal),
+        _ => None,
+    });
+    for literal in literals {
+        let components = literal.split('/').collect::<Vec<_>>();
+        for &(ident, ref route) in routes {
+            // Whether the route takes any address below its own, rather than only its own.
+            let takes_remainder = route.rest.is_some() || matches!(route.msg_args_type, MsgArgsType::Struct);
+            for route_address in route.addresses() {
+                match *route_address {
+                    OscBranchFmt::Str(ref prefix) if prefix == literal => {
+                        return Some(format!("The address \"/{}\" is already routed to the variant `{}`, which is tried first; remove one of them, or add #[osc_address(allow_overlap)] if this is intended", literal, ident));
+                    },
+                    OscBranchFmt::Str(ref prefix) if takes_remainder && literal.starts_with(&format!("{}/", prefix)) => {
+                        return Some(format!("The address \"/{}\" is shadowed by the variant `{}`, which is tried first and captures everything below \"/{}\"; declare this variant before it, or add #[osc_address(allow_overlap)] if this is intended", literal, ident, prefix));
+                    },
+                    OscBranchFmt::Fmt(ref template) if route.parse_with.is_none() && template.len() <= components.len() && (takes_remainder || template.len() == components.len()) => {
+                        if let PathArgsType::One(ref ty) = route.path_args_type {
+                            if template_parses(template, ty, &components[..template.len()], generics) {
+                                let template_str = template.iter().map(|literals| literals.join("{}")).collect::<Vec<_>>().join("/");
+                                return Some(format!("The address \"/{}\" is shadowed by the variant `{}`, which is tried first and whose address \"/{}\" matches it; declare this variant before it, or add #[osc_address(allow_overlap)] if this is intended", literal, ident, template_str));
+                            }
+                        }
+                    },
+                    OscBranchFmt::None => if let (PathArgsType::One(ref ty), None) = (&route.path_args_type, &route.parse_with) {
+                        if parses_as(components[0], ty, generics) {
+                            return Some(format!("The address \"/{}\" is shadowed by the variant `{}`, which is tried first and whose path argument of type `{}` parses \"{}\"; declare this variant before it, or add #[osc_address(allow_overlap)] if this is intended", literal, ident, quote! { #ty }, components[0]));
+                        }
+                    },
+                    _ => {},
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Return whether the components of a literal address fit a format-string
+/// address, e.g. "ch3" fits "ch{}" with a `u8` path argument: each component
+/// splits around the literal text of the template as it does when routing, and
+/// each placeholder's text is known to parse as its type.
+fn template_parses(template: &[Vec<String>], ty: &Ty, components: &[&str], generics: &syn::Generics) -> bool {
+    // Each placeholder binds to an element of a tuple path argument, or else to the whole of it.
+    let tys = match *ty {
+        Ty::Tup(ref tys) if !tys.is_empty() => tys.iter().collect::<Vec<_>>(),
+        _ => vec![ty],
+    };
+    let mut tys = tys.into_iter();
+    template.iter().zip(components).all(|(literals, component)| {
+        let literals = literals.iter().map(String::as_str).collect::<Vec<_>>();
+        let mut fields = vec![""; literals.len().saturating_sub(1)];
+        osc_address::match_component(component, &literals, &mut fields) && fields.into_iter().all(|field| match tys.next() {
+            Some(ty) => parses_as(field, ty, generics),
+            None => false,
+        })
+    })
+}
+
+/// Return whether `text` is known to parse as the given type via `FromStr`.
+/// Only primitive types and `String` are known, by their plain name or their
+/// full path, e.g. `u32` or `std::primitive::u32`. A plain name which is
+/// also a type parameter of the enum is taken to be that parameter.
+fn parses_as(text: &str, ty: &Ty, generics: &syn::Generics) -> bool {
+    let path = match *ty {
+        Ty::Path(None, ref path) if path.segments.iter().all(|segment| segment.parameters.is_empty()) => path,
+        _ => return false,
+    };
+    let segments = path.segments.iter().map(|segment| segment.ident.as_ref()).collect::<Vec<_>>();
+    let name = match segments[..] {
+        [name] if !path.global => {
+            if generics.ty_params.iter().any(|param| param.ident == name) {
+                return false;
+            }
+            name
+        },
+        ["std", "primitive", name] | ["core", "primitive", name] if name != "String" => name,
+        ["std", "string", "String"] | ["alloc", "string", "String"] => "String",
+        _ => return false,
+    };
+    match name {
+        "u8" => text.parse::<u8>().is_ok(),
+        "u16" => text.parse::<u16>().is_ok(),
+        "u32" => text.parse::<u32>().is_ok(),
+        "u64" => text.parse::<u64>().is_ok(),
+        "u128" => text.parse::<u128>().is_ok(),
+        "usize" => text.parse::<usize>().is_ok(),
+        "i8" => text.parse::<i8>().is_ok(),
+        "i16" => text.parse::<i16>().is_ok(),
+        "i32" => text.parse::<i32>().is_ok(),
+        "i64" => text.parse::<i64>().is_ok(),
+        "i128" => text.parse::<i128>().is_ok(),
+        "isize" => text.parse::<isize>().is_ok(),
+        "f32" => text.parse::<f32>().is_ok(),
+        "f64" => text.parse::<f64>().is_ok(),
+        "bool" => text.parse::<bool>().is_ok(),
+        "char" => text.parse::<char>().is_ok(),
+        "String" => true,
+        _ => false,
+    }
+}
+
+/// Return the span of the item which an error is reported against, as found
+/// in the tokens of the type deriving OscMessage.
+fn error_span(input: &TokenStream, target: &ErrorTarget) -> Span {
+    let mut tokens = input.clone().into_iter();
+    let mut container_span = None;
+    // Skip the attributes and visibility of the type, up to its name.
+    for token in tokens.by_ref() {
+        match token {
+            TokenTree::Group(ref group) if group.delimiter() == Delimiter::Bracket && is_osc_attr(group) => {
+                container_span = container_span.or_else(|| Some(group.span()));
+            },
+            TokenTree::Ident(ref ident) if ident.to_string() == "enum" || ident.to_string() == "struct" => break,
+            _ => {},
+        }
+    }
+    let name_span = tokens.next().map_or_else(Span::call_site, |name| name.span());
+    let (variant_name, attr_index) = match *target {
+        ErrorTarget::Container => return container_span.unwrap_or(name_span),
+        ErrorTarget::Variant(ref ident) => (ident.as_ref(), None),
+        ErrorTarget::Attribute(ref ident, index) => (ident.as_ref(), Some(index)),
+    };
+    let body = tokens.filter_map(|token| match token {
+        TokenTree::Group(ref group) if group.delimiter() == Delimiter::Brace => Some(group.stream()),
+        _ => None,
+    }).next();
+    // Each variant is preceded by the start of the body or a comma, plus any attributes.
+    let mut at_variant = true;
+    let mut attr_spans = Vec::new();
+    for token in body.into_iter().flatten() {
+        match token {
+            TokenTree::Group(ref group) if at_variant && group.delimiter() == Delimiter::Bracket && is_osc_attr(group) => {
+                attr_spans.push(group.span());
+            },
+            TokenTree::Ident(ref ident) if at_variant => {
+                if ident.to_string() == variant_name {
+                    return attr_index.and_then(|index| attr_spans.get(index).cloned()).unwrap_or_else(|| ident.span());
+                }
+                at_variant = false;
+            },
+            TokenTree::Punct(ref punct) if punct.as_char() == ',' => {
+                at_variant = true;
+                attr_spans.clear();
+            },
+            _ => {},
         }
     }
-    OscRouteProperties{ address, path_args_type, msg_args_type }
+    name_span
+}
+
+/// Return whether the contents of an attribute, e.g. `[osc_address(..)]`, are
+/// one of this crate's, in the form which `get_osc_attr_items` counts.
+fn is_osc_attr(group: &Group) -> bool {
+    let mut tokens = group.stream().into_iter();
+    match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Ident(ref ident)), Some(TokenTree::Group(ref args))) => {
+            ident.to_string() == "osc_address" && args.delimiter() == Delimiter::Parenthesis
+        },
+        _ => false,
+    }
+}
+
+/// Return a `compile_error!` invocation which reports `message` at `span`.
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let mut literal = Literal::string(message);
+    literal.set_span(span);
+    let mut args = Group::new(Delimiter::Parenthesis, TokenTree::from(literal).into());
+    args.set_span(span);
+    let mut bang = Punct::new('!', Spacing::Alone);
+    bang.set_span(span);
+    let mut semi = Punct::new(';', Spacing::Alone);
+    semi.set_span(span);
+    vec![
+        TokenTree::from(proc_macro::Ident::new("compile_error", span)),
+        TokenTree::from(bang),
+        TokenTree::from(args),
+        TokenTree::from(semi),
+    ].into_iter().collect()
+}
+
+/// Return the where-clause predicates which the generated code requires of the
+/// fields of a variant. Only fields whose type involves one of the type
+/// parameters are bounded, as the others are known to satisfy them.
+fn get_variant_bounds(variant: &syn::Variant, container: &ContainerProperties, generics: &syn::Generics) -> Vec<quote::Tokens> {
+    let variant_props = get_variant_props(variant, container);
+    // Custom parse and format functions stand in for the traits.
+    let path_bound = match (variant_props.fallback, &variant_props.format_with, &variant_props.parse_with) {
+        (true, _, _) => quote! { std::convert::AsRef<str> + std::str::FromStr },
+        (false, None, None) => quote! { std::fmt::Display + std::str::FromStr },
+        (false, Some(_), None) => quote! { std::str::FromStr },
+        (false, None, Some(_)) => quote! { std::fmt::Display },
+        (false, Some(_), Some(_)) => quote! { Sized },
+    };
+    let msg_bound = match variant_props.msg_args_type {
+        // Payloads which borrow from the input are never replayed from the
+        // arguments captured when matching patterns; the others must be owned.
+        MsgArgsType::Seq if !variant_props.borrowed.is_empty() => quote! { serde::Serialize + serde::Deserialize<'de> },
+        MsgArgsType::Seq => quote! { serde::Serialize + serde::de::DeserializeOwned },
+        MsgArgsType::Struct => quote! { osc_address::OscMessage<'de> },
+    };
+    // Each element of a tuple path argument is formatted and parsed separately.
+    let path_tys = match (&variant_props.path_args_type, &variant_props.address) {
+        (PathArgsType::Unit, _) => Vec::new(),
+        (PathArgsType::One(Ty::Tup(elems)), OscBranchFmt::Fmt(_)) => elems.clone(),
+        (PathArgsType::One(ty), _) => vec![ty.clone()],
+    };
+    let path_bounds = path_tys.into_iter().map(|ty| (ty, path_bound.clone()));
+    // The captured remainder of the address is parsed and emitted as a whole.
+    let rest_bounds = variant_props.rest.into_iter().map(|ty| (ty, quote! { std::convert::AsRef<str> + std::str::FromStr }));
+    let msg_bounds = get_msg_tys(variant).into_iter().map(|ty| (ty, msg_bound.clone()));
+    path_bounds.chain(rest_bounds).chain(msg_bounds).filter(|(ty, _)| mentions_generic_param(ty, generics)).map(|(ty, bound)| quote! {
+        #ty: #bound
+    }).collect()
+}
+
+/// Return the types of the fields of a variant which form its payload.
+fn get_msg_tys(variant: &syn::Variant) -> Vec<Ty> {
+    match variant.data {
+        syn::VariantData::Unit => Vec::new(),
+        syn::VariantData::Tuple(ref fields) => fields.last().map(|field| field.ty.clone()).into_iter().collect(),
+        syn::VariantData::Struct(ref fields) => fields.iter().filter(|field| !is_path_field(field) && !is_rest_field(field)).map(|field| field.ty.clone()).collect(),
+    }
+}
+
+/// Return whether a type involves any of the type or lifetime parameters of `generics`.
+fn mentions_generic_param(ty: &Ty, generics: &syn::Generics) -> bool {
+    let tokens = quote! { #ty }.to_string();
+    tokens.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '\''))
+        .any(|word| generics.ty_params.iter().any(|param| param.ident == word)
+            || generics.lifetimes.iter().any(|def| def.lifetime.ident == word))
+}
+
+/// Collect the lifetimes of all references within a type, e.g. 'a for `(&'a str, i32)`.
+fn borrowed_lifetimes(ty: &Ty, lifetimes: &mut Vec<syn::Lifetime>) {
+    match *ty {
+        Ty::Rptr(ref lifetime, ref mut_ty) => {
+            lifetimes.extend(lifetime.clone());
+            borrowed_lifetimes(&mut_ty.ty, lifetimes);
+        },
+        Ty::Slice(ref ty) | Ty::Array(ref ty, _) | Ty::Paren(ref ty) => borrowed_lifetimes(ty, lifetimes),
+        Ty::Tup(ref tys) => for ty in tys {
+            borrowed_lifetimes(ty, lifetimes);
+        },
+        Ty::Path(_, ref path) => for segment in &path.segments {
+            if let syn::PathParameters::AngleBracketed(ref data) = segment.parameters {
+                for ty in &data.types {
+                    borrowed_lifetimes(ty, lifetimes);
+                }
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Return the identifier of the #[osc_address(fallback)] variant, if any.
+fn get_fallback(variants: &[syn::Variant], container: &ContainerProperties) -> Option<syn::Ident> {
+    variants.iter().find(|variant| get_variant_props(variant, container).fallback).map(|variant| variant.ident.clone())
+}
+
+/// Return the number of elements of a (non-unit) tuple type, if `ty` is one.
+fn tuple_len(ty: &Ty) -> Option<usize> {
+    match *ty {
+        Ty::Tup(ref elems) if !elems.is_empty() => Some(elems.len()),
+        _ => None,
+    }
+}
+
+/// Return the expressions which fill the placeholders of a format-string
+/// address, given a `path_arg` reference in scope: either the path argument
+/// itself, or each element of a tuple path argument.
+fn path_arg_fields(placeholders: usize, path_args_type: &PathArgsType) -> Vec<quote::Tokens> {
+    match *path_args_type {
+        PathArgsType::One(ref ty) if tuple_len(ty).is_some() => (0..placeholders).map(|i| {
+            // Build the index by hand; quote would emit `0usize`.
+            let index = syn::Ident::new(i.to_string());
+            quote! { path_arg.#index }
+        }).collect(),
+        _ => vec![quote! { path_arg }],
+    }
+}
+
+/// Return the total number of placeholders in a format-string address.
+fn num_placeholders(template: &[Vec<String>]) -> usize {
+    template.iter().map(|literals| literals.len() - 1).sum()
+}
+
+/// Return an expression that matches the leading components of `input` (an
+/// address or address pattern) against a format-string address via `function`
+/// (`osc_address::match_template` or `osc_address::match_pattern_template`),
+/// yielding `Some((path_arg, downstream))` if they fit the format and each
+/// placeholder parses.
+fn parse_template(template: &[Vec<String>], path_args_type: &PathArgsType, parse_with: &Option<syn::Path>, function: quote::Tokens, input: quote::Tokens) -> quote::Tokens {
+    let placeholders = num_placeholders(template);
+    let parsed = (0..placeholders).map(|i| syn::Ident::new(format!("__path_arg_{}", i))).collect::<Vec<_>>();
+    let parsed2 = parsed.clone();
+    // Name the type of each placeholder, as the caller may discard the path argument.
+    let (path_arg, field_tys) = match *path_args_type {
+        PathArgsType::One(Ty::Tup(ref elems)) if !elems.is_empty() => (quote! { (#(#parsed2,)*) }, elems.clone()),
+        PathArgsType::One(ref ty) => (quote! { #(#parsed2)* }, vec![ty.clone()]),
+        PathArgsType::Unit => unreachable!("rejected by get_variant_props"),
+    };
+    let parses = field_tys.into_iter().enumerate().map(|(i, ty)| {
+        let index = syn::Ident::new(i.to_string());
+        parse_path_arg(quote! { fields[#index] }, &PathArgsType::One(ty), parse_with)
+    });
+    let parsed3 = parsed.clone();
+    let components = template.iter().map(|literals| quote! { &[#(#literals),*][..] });
+    quote! {
+        {
+            let mut fields = [""; #placeholders];
+            match #function(#input, &[#(#components),*], &mut fields) {
+                Some(downstream) => match (#(#parses,)*) {
+                    (#(Ok(#parsed3),)*) => Some((#path_arg, downstream)),
+                    _ => None,
+                },
+                None => None,
+            }
+        }
+    }
+}
+
+/// Return an expression which parses `text` into a path argument of the given
+/// type, yielding a `Result`, via #[osc_address(parse_with=...)] if given.
+fn parse_path_arg(text: quote::Tokens, path_args_type: &PathArgsType, parse_with: &Option<syn::Path>) -> quote::Tokens {
+    match (parse_with, path_args_type) {
+        (Some(parse_with), _) => quote! { #parse_with(#text) },
+        (None, PathArgsType::One(ty)) => quote! { #text.parse::<#ty>() },
+        (None, PathArgsType::Unit) => unreachable!("rejected by get_variant_props"),
+    }
+}
+
+/// Return an expression which displays the path argument (or an element of it)
+/// referenced by `field`, via #[osc_address(format_with=...)] if given.
+fn display_path_arg(field: quote::Tokens, format_with: &Option<syn::Path>) -> quote::Tokens {
+    match *format_with {
+        Some(ref format_with) => quote! { osc_address::DisplayWith(#field, #format_with) },
+        None => field,
+    }
+}
+
+/// Return whether a field of a struct-like variant is annotated with
+/// #[osc_address(path)], i.e. is parsed from the address.
+fn is_path_field(field: &syn::Field) -> bool {
+    get_osc_meta_items(&field.attrs).any(|item| match *item {
+        NestedMetaItem::MetaItem(MetaItem::Word(ref name)) => name == "path",
+        _ => false,
+    })
+}
+
+/// Return whether a field of a variant is annotated with #[osc_address(rest)],
+/// i.e. captures the remainder of the address.
+fn is_rest_field(field: &syn::Field) -> bool {
+    get_osc_meta_items(&field.attrs).any(|item| match *item {
+        NestedMetaItem::MetaItem(MetaItem::Word(ref name)) => name == "rest",
+        _ => false,
+    })
+}
+
+/// Verify that a field of a variant only carries supported directives.
+fn check_field_attrs(field: &syn::Field) -> Result<(), String> {
+    let field_name = field.ident.as_ref().map_or("", |ident| ident.as_ref());
+    for item in get_osc_meta_items(&field.attrs) {
+        match *item {
+            NestedMetaItem::MetaItem(MetaItem::Word(ref name)) if name == "path" && field.ident.is_some() => {},
+            NestedMetaItem::MetaItem(MetaItem::Word(ref name)) if name == "rest" => {},
+            _ => return Err(format!("Unsupported directive #[osc_address({})] on the field `{}`; expected `path` or `rest`", quote! { #item }, field_name)),
+        }
+    }
+    if is_path_field(field) && is_rest_field(field) {
+        return Err(format!("The field `{}` may be either a #[osc_address(path)] or an #[osc_address(rest)] field, but not both", field_name));
+    }
+    Ok(())
+}
+
+/// Return a pattern matching a `&self` of the given variant, along with
+/// statements binding references `path_arg` and `msg_data` to its path
+/// argument (if any) and payload, and `rest` to the remainder of its address
+/// (if captured).
+fn destructure_variant(typename: &syn::Ident, variant_ident: &syn::Ident, shape: &VariantShape) -> (quote::Tokens, quote::Tokens) {
+    match *shape {
+        VariantShape::Tuple => (quote! {
+            #typename::#variant_ident(ref path_arg, ref msg_data)
+        }, quote! {}),
+        VariantShape::Newtype => (quote! {
+            #typename::#variant_ident(ref msg_data)
+        }, quote! {}),
+        VariantShape::Unit => (quote! {
+            #typename::#variant_ident
+        }, quote! {
+            let msg_data = &();
+        }),
+        VariantShape::Rest => (quote! {
+            #typename::#variant_ident(ref rest, ref msg_data)
+        }, quote! {}),
+        VariantShape::Struct{ ref path_fields, ref rest_field, ref arg_fields } => {
+            // Bind the fields under private names, so that they can't shadow
+            // any of the variables of the generated code.
+            let bound_path_fields = &private_idents(path_fields);
+            let bound_arg_fields = &private_idents(arg_fields);
+            let path_arg = match bound_path_fields.len() {
+                0 => quote! { &() },
+                1 => quote! { #(#bound_path_fields)* },
+                _ => quote! { &(#(#bound_path_fields,)*) },
+            };
+            let rest_fields = rest_field.iter().collect::<Vec<_>>();
+            let bound_rest_fields = &private_idents(&rest_field.iter().cloned().collect::<Vec<_>>());
+            (quote! {
+                #typename::#variant_ident{ #(#path_fields: ref #bound_path_fields,)* #(#rest_fields: ref #bound_rest_fields,)* #(#arg_fields: ref #bound_arg_fields,)* }
+            }, quote! {
+                let path_arg = #path_arg;
+                #(let rest = #bound_rest_fields;)*
+                let msg_data = &(#(#bound_arg_fields,)*);
+            })
+        },
+    }
+}
+
+/// Return an expression constructing the given variant from expressions
+/// evaluating to its path argument and payload. For a variant which captures
+/// the remainder of the address, `path_arg` evaluates to a pair of its path
+/// argument and that remainder.
+fn construct_variant(typename: &syn::Ident, variant_ident: &syn::Ident, shape: &VariantShape, path_arg: quote::Tokens, msg_data: quote::Tokens) -> quote::Tokens {
+    match *shape {
+        VariantShape::Tuple => quote! {
+            #typename::#variant_ident(#path_arg, #msg_data)
+        },
+        // There is no path argument, so #path_arg is always ().
+        VariantShape::Newtype => quote! {
+            #typename::#variant_ident(#msg_data)
+        },
+        VariantShape::Unit => quote! {
+            {
+                let () = #msg_data;
+                #typename::#variant_ident
+            }
+        },
+        VariantShape::Rest => quote! {
+            {
+                let ((), rest) = #path_arg;
+                #typename::#variant_ident(rest, #msg_data)
+            }
+        },
+        VariantShape::Struct{ ref path_fields, ref rest_field, ref arg_fields } => {
+            let bound_path_fields = &private_idents(path_fields);
+            let bound_arg_fields = &private_idents(arg_fields);
+            let mut path_pattern = match bound_path_fields.len() {
+                0 => quote! { () },
+                1 => quote! { #(#bound_path_fields)* },
+                _ => quote! { (#(#bound_path_fields,)*) },
+            };
+            let rest_fields = rest_field.iter().collect::<Vec<_>>();
+            let bound_rest_fields = &private_idents(&rest_field.iter().cloned().collect::<Vec<_>>());
+            if rest_field.is_some() {
+                path_pattern = quote! { (#path_pattern, #(#bound_rest_fields)*) };
+            }
+            quote! {
+                {
+                    let #path_pattern = #path_arg;
+                    let (#(#bound_arg_fields,)*) = #msg_data;
+                    #typename::#variant_ident{ #(#path_fields: #bound_path_fields,)* #(#rest_fields: #bound_rest_fields,)* #(#arg_fields: #bound_arg_fields,)* }
+                }
+            }
+        },
+    }
+}
+
+/// Return the names under which the generated code binds the given fields.
+fn private_idents(fields: &[syn::Ident]) -> Vec<syn::Ident> {
+    fields.iter().map(|field| syn::Ident::new(format!("__field_{}", field))).collect()
 }
 
 /// Return all NestedMetaItems corresponding to
 /// #[osc_address ...] attributes
-fn get_osc_meta_items<'a>(variant: &'a syn::Variant) -> impl Iterator<Item=&'a syn::NestedMetaItem> + 'a {
-    variant.attrs.iter().flat_map(|attr| match attr.value {
-        MetaItem::List(ref name, ref items) if name == "osc_address" => Some(items.iter()),
+fn get_osc_meta_items<'a>(attrs: &'a [syn::Attribute]) -> impl Iterator<Item=&'a syn::NestedMetaItem> + 'a {
+    get_osc_attr_items(attrs).map(|(_, item)| item)
+}
+
+/// Like `get_osc_meta_items`, but pairs each item with the index of the
+/// #[osc_address(..)] attribute which holds it, counting only those attributes.
+fn get_osc_attr_items<'a>(attrs: &'a [syn::Attribute]) -> impl Iterator<Item=(usize, &'a syn::NestedMetaItem)> + 'a {
+    attrs.iter().filter_map(|attr| match attr.value {
+        MetaItem::List(ref name, ref items) if name == "osc_address" => Some(items),
         _ => None,
-    }).flat_map(|attrs| attrs)
+    }).enumerate().flat_map(|(index, items)| items.iter().map(move |item| (index, item)))
 }
 
 impl OscBranchFmt {
-    fn new(fmt: &syn::Lit) -> Self {
+    fn new(fmt: &syn::Lit) -> Result<Self, String> {
         match *fmt {
-            syn::Lit::Str(ref s, ref _style) => OscBranchFmt::Str(s.clone()),
-            _ => panic!("Expected a string in #[osc_address(address=...)]; got: {:?}", fmt),
+            syn::Lit::Str(ref s, ref _style) if s.contains("{}") => {
+                // Components with placeholders, e.g. "ch{}", "{}_{}" or "grid/{}/{}/press".
+                let template = s.split('/').map(|component| {
+                    let literals = component.split("{}").map(str::to_owned).collect::<Vec<_>>();
+                    let adjacent = literals.len() > 2 && literals[1..literals.len() - 1].iter().any(String::is_empty);
+                    if component.is_empty() || adjacent || literals.iter().any(|literal| literal.contains(|c| FORBIDDEN_CHARS.contains(c))) {
+                        return Err(format!("\"{}\" is not a valid OSC address: each of its '/'-separated components must be non-empty, adjacent {{}} placeholders must be separated by literal text, and literal text may not contain any of \"{}\"", s, FORBIDDEN_CHARS));
+                    }
+                    Ok(literals)
+                }).collect::<Result<_, _>>()?;
+                Ok(OscBranchFmt::Fmt(template))
+            },
+            syn::Lit::Str(ref s, ref _style) => {
+                // The literal may span several components, e.g. "mixer/master/volume".
+                if s.split('/').any(|component| component.is_empty() || component.contains(|c| FORBIDDEN_CHARS.contains(c))) {
+                    return Err(format!("\"{}\" is not a valid OSC address: each of its '/'-separated components must be non-empty and may not contain any of \"{}\"", s, FORBIDDEN_CHARS));
+                }
+                Ok(OscBranchFmt::Str(s.clone()))
+            },
+            _ => Err(format!("Expected a string in #[osc_address(address=...)], but found {}", quote! { #fmt })),
         }
     }
 }
 
+impl OscRouteProperties {
+    /// Return the canonical address of the variant, followed by its aliases.
+    fn addresses(&self) -> impl Iterator<Item=&OscBranchFmt> {
+        Some(&self.address).into_iter().chain(&self.aliases)
+    }
+    /// Return whether the variant captures the whole of the address which
+    /// reaches it, i.e. has an #[osc_address(rest)] field and no address.
+    fn captures_whole_address(&self) -> bool {
+        self.rest.is_some() && self.address == OscBranchFmt::None && self.path_args_type == PathArgsType::Unit
+    }
+}
+
+impl PayloadKind {
+    fn new(kind: &str) -> Result<Self, String> {
+        match kind {
+            "args" => Ok(PayloadKind::Args),
+            "message" => Ok(PayloadKind::Message),
+            "struct" => Ok(PayloadKind::Struct),
+            _ => Err(format!("Unknown #[osc_address(payload=\"{}\")]; expected one of \"args\", \"message\", \"struct\"", kind)),
+        }
+    }
+}
+
+impl RenameRule {
+    fn new(rule: &str) -> Result<Self, String> {
+        match rule {
+            "lowercase" => Ok(RenameRule::Lower),
+            "UPPERCASE" => Ok(RenameRule::Upper),
+            "PascalCase" => Ok(RenameRule::Pascal),
+            "camelCase" => Ok(RenameRule::Camel),
+            "snake_case" => Ok(RenameRule::Snake),
+            "SCREAMING_SNAKE_CASE" => Ok(RenameRule::ScreamingSnake),
+            "kebab-case" => Ok(RenameRule::Kebab),
+            "SCREAMING-KEBAB-CASE" => Ok(RenameRule::ScreamingKebab),
+            _ => Err(format!("Unknown #[osc_address(rename_all=\"{}\")]; expected one of \"lowercase\", \"UPPERCASE\", \"PascalCase\", \"camelCase\", \"snake_case\", \"SCREAMING_SNAKE_CASE\", \"kebab-case\", \"SCREAMING-KEBAB-CASE\"", rule)),
+        }
+    }
+    /// Convert a PascalCase variant name according to this rule.
+    fn apply(self, variant: &str) -> String {
+        match self {
+            RenameRule::Lower => variant.to_ascii_lowercase(),
+            RenameRule::Upper => variant.to_ascii_uppercase(),
+            RenameRule::Pascal => variant.to_owned(),
+            RenameRule::Camel => {
+                let mut chars = variant.chars();
+                chars.next().map(|first| first.to_ascii_lowercase()).into_iter().chain(chars).collect()
+            },
+            RenameRule::Snake => {
+                let mut snake = String::new();
+                for (i, c) in variant.char_indices() {
+                    if i > 0 && c.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(c.to_ascii_lowercase());
+                }
+                snake
+            },
+            RenameRule::ScreamingSnake => RenameRule::Snake.apply(variant).to_ascii_uppercase(),
+            RenameRule::Kebab => RenameRule::Snake.apply(variant).replace('_', "-"),
+            RenameRule::ScreamingKebab => RenameRule::ScreamingSnake.apply(variant).replace('_', "-"),
+        }
+    }
+}
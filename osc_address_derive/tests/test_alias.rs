@@ -0,0 +1,55 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+use osc_address::{decode, encode, OscMatches, OscMessage};
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    #[osc_address(address="volume", alias="vol")]
+    Volume((), (f32,)),
+    #[osc_address(address="fader{}", alias="{}/fader", alias="f/{}")]
+    Fader(u8, (f32,)),
+    #[osc_address(address="mixer", alias="1/mixer")]
+    Mixer((), MsgMixer),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgMixer {
+    #[osc_address(address="mute")]
+    Mute((), (i32,)),
+}
+
+#[test]
+fn aliases() {
+    let cases = [
+        ("/volume", MsgRoot::Volume((), (0.5,))),
+        ("/vol", MsgRoot::Volume((), (0.5,))),
+        ("/fader3", MsgRoot::Fader(3, (0.5,))),
+        ("/3/fader", MsgRoot::Fader(3, (0.5,))),
+        ("/f/3", MsgRoot::Fader(3, (0.5,))),
+    ];
+    for &(address, ref expected) in cases.iter() {
+        let msg: MsgRoot = decode(&encode(&(address, (0.5f32,))).unwrap()).unwrap();
+        assert_eq!(msg, *expected, "{}", address);
+    }
+    let msg: MsgRoot = decode(&encode(&("/1/mixer/mute", (1,))).unwrap()).unwrap();
+    assert_eq!(msg, MsgRoot::Mixer((), MsgMixer::Mute((), (1,))));
+}
+
+#[test]
+fn canonical_address() {
+    assert_eq!(MsgRoot::Volume((), (0.5,)).get_address().unwrap(), "/volume");
+    assert_eq!(MsgRoot::Fader(3, (0.5,)).get_address().unwrap(), "/fader3");
+    assert_eq!(MsgRoot::Mixer((), MsgMixer::Mute((), (1,))).get_address().unwrap(), "/mixer/mute");
+}
+
+#[test]
+fn pattern() {
+    // A pattern matching both the address and an alias yields the message once.
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/vol*", (0.5f32,))).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Volume((), (0.5,))]);
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/?/mixer/mute", (1,))).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Mixer((), MsgMixer::Mute((), (1,)))]);
+}
@@ -0,0 +1,103 @@
+//! Verify that routing a message to its leaf payload performs no allocation.
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+extern crate serde;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use osc_address::{decode, encode};
+use serde::Deserialize;
+use serde::de::{DeserializeSeed, IntoDeserializer, SeqAccess};
+use serde::de::value::{Error, SeqAccessDeserializer, SeqDeserializer};
+
+/// Allocator that counts the allocations made by a thread while it is `COUNTING`.
+struct CountingAllocator;
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+thread_local!(static COUNTING: Cell<bool> = const { Cell::new(false) });
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.with(|counting| counting.get()) {
+            ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        }
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgLeaf {
+    #[osc_address(address="first")]
+    First((), ()),
+    Second(u8, (i32, i32)),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    #[osc_address(address="left")]
+    Left((), MsgLeaf),
+    #[osc_address(address="right")]
+    Right((), MsgRoot2),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot2 {
+    #[osc_address(address="down")]
+    Down((), MsgLeaf),
+}
+
+/// A message whose deserializer lends out neither its address nor its
+/// arguments, like serde_osc, but which otherwise doesn't allocate.
+struct Message<'a> {
+    address: Option<&'a str>,
+    args: Option<[i32; 2]>,
+}
+impl<'de, 'a> SeqAccess<'de> for Message<'a> {
+    type Error = Error;
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+        if let Some(address) = self.address.take() {
+            return seed.deserialize(address.into_deserializer()).map(Some);
+        }
+        match self.args.take() {
+            None => Ok(None),
+            Some(args) => seed.deserialize(SeqDeserializer::new(args.iter().cloned())).map(Some),
+        }
+    }
+}
+
+/// Run `f`, counting the allocations it makes.
+fn count_allocations<T, F: FnOnce() -> T>(f: F) -> (T, usize) {
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    COUNTING.with(|counting| counting.set(true));
+    let result = f();
+    COUNTING.with(|counting| counting.set(false));
+    (result, ALLOCATIONS.load(Ordering::SeqCst) - before)
+}
+
+#[test]
+fn route_without_allocating() {
+    let expected = MsgRoot::Right((), MsgRoot2::Down((), MsgLeaf::Second(7, (1, 2))));
+    let encoded = encode(&expected).unwrap();
+    let (parsed, allocations) = count_allocations(|| decode::<MsgRoot>(&encoded));
+    assert_eq!(parsed.unwrap(), expected);
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn route_copied_address_without_allocating() {
+    let expected = MsgRoot::Right((), MsgRoot2::Down((), MsgLeaf::Second(7, (1, 2))));
+    let (parsed, allocations) = count_allocations(|| {
+        MsgRoot::deserialize(SeqAccessDeserializer::new(Message{ address: Some("/right/down/7"), args: Some([1, 2]) }))
+    });
+    assert_eq!(parsed.unwrap(), expected);
+    assert_eq!(allocations, 0);
+}
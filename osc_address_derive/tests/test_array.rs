@@ -0,0 +1,49 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+use osc_address::{decode, encode, OscMatches, OscMessage};
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    #[osc_address(address="levels")]
+    Levels((), (String, Vec<f32>)),
+    #[osc_address(address="grid")]
+    Grid((), (Vec<Vec<i32>>, [bool; 2])),
+}
+
+fn messages() -> Vec<MsgRoot> {
+    vec![
+        MsgRoot::Levels((), ("out".to_owned(), vec![0.5, 1.0])),
+        MsgRoot::Grid((), (vec![vec![1], vec![], vec![2, 3]], [true, false])),
+    ]
+}
+
+#[test]
+fn type_tags() {
+    let messages = messages();
+    let levels = encode(&messages[0]).unwrap();
+    assert_eq!(&levels[..16], b"/levels\0,s[ff]\0\0");
+    let grid = encode(&messages[1]).unwrap();
+    assert_eq!(&grid[..28], b"/grid\0\0\0,[[i][][ii]][TF]\0\0\0\0");
+    assert_eq!(&grid[28..], &[0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3]);
+}
+
+#[test]
+fn round_trip() {
+    for msg in messages() {
+        let decoded: MsgRoot = decode(&encode(&msg).unwrap()).unwrap();
+        assert_eq!(decoded, msg);
+    }
+}
+
+#[test]
+fn pattern() {
+    for msg in messages() {
+        let address = msg.get_address().unwrap();
+        let mut encoded = encode(&msg).unwrap();
+        encoded[1] = b'?';
+        let parsed: OscMatches<MsgRoot> = decode(&encoded).unwrap();
+        assert_eq!(parsed.into_messages(), vec![msg], "{}", address);
+    }
+}
@@ -0,0 +1,64 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+use osc_address::{decode, encode, OscMatches};
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot<'a> {
+    #[osc_address(address="chunk")]
+    Chunk((), (&'a [u8],)),
+    #[osc_address(address="name{}")]
+    Name(u8, (&'a str, i32)),
+    #[osc_address(address="volume")]
+    Volume((), (f32,)),
+    #[osc_address(address="storage")]
+    Storage((), MsgStorage<'a>),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgStorage<'b> {
+    Rename { #[osc_address(path)] slot: u32, name: &'b str },
+}
+
+#[test]
+fn zero_copy() {
+    let packet = encode(&MsgRoot::Chunk((), (b"\x01\x02\x03",))).unwrap();
+    let msg: MsgRoot = decode(&packet).unwrap();
+    let data = match msg {
+        MsgRoot::Chunk((), (data,)) => data,
+        other => panic!("unexpected message: {:?}", other),
+    };
+    assert_eq!(data, b"\x01\x02\x03");
+    // The blob points into the packet rather than into a copy of it.
+    let packet_range = packet.as_ptr() as usize..packet.as_ptr() as usize + packet.len();
+    assert!(packet_range.contains(&(data.as_ptr() as usize)));
+}
+
+#[test]
+fn round_trip() {
+    let messages = vec![
+        MsgRoot::Chunk((), (b"\xff\x00",)),
+        MsgRoot::Name(2, ("kick", 1)),
+        MsgRoot::Volume((), (0.5,)),
+        MsgRoot::Storage((), MsgStorage::Rename{ slot: 16, name: "snare" }),
+    ];
+    for msg in messages {
+        let packet = encode(&msg).unwrap();
+        let decoded: MsgRoot = decode(&packet).unwrap();
+        assert_eq!(decoded, msg);
+    }
+}
+
+#[test]
+fn pattern() {
+    // Owned arguments can still be matched...
+    let packet = encode(&("/vol*", (0.5f32,))).unwrap();
+    let parsed: OscMatches<MsgRoot> = decode(&packet).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Volume((), (0.5,))]);
+    // ...but borrowed ones can't be lent from the replayed arguments.
+    let packet = encode(&("/chu*", (&[1u8, 2, 3][..],))).unwrap();
+    let parsed: Result<OscMatches<MsgRoot>, _> = decode(&packet);
+    assert!(parsed.is_err());
+}
@@ -0,0 +1,71 @@
+#[macro_use]
+extern crate serde_derive;
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+extern crate serde_osc;
+use osc_address::{decode, encode, OscMatches};
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    #[osc_address(address="left")]
+    Left((), (i32, f32)),
+    #[osc_address(address="right")]
+    Right((), MsgRight),
+    #[osc_address(address="empty")]
+    Empty((), ()),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRight {
+    #[osc_address(address="say")]
+    Say((), (String,)),
+    ByIndex(u32, Payload),
+}
+
+#[derive(OscMessage)]
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, PartialEq)]
+struct Payload {
+    name: String,
+    value: f32,
+}
+
+fn messages() -> Vec<MsgRoot> {
+    vec![
+        MsgRoot::Left((), (-3, 0.25)),
+        MsgRoot::Right((), MsgRight::Say((), ("hello".to_owned(),))),
+        MsgRoot::Right((), MsgRight::ByIndex(12, Payload{ name: "level".to_owned(), value: 0.5 })),
+        MsgRoot::Empty((), ()),
+    ]
+}
+
+#[test]
+fn round_trip() {
+    for msg in messages() {
+        let encoded = encode(&msg).unwrap();
+        let decoded: MsgRoot = decode(&encoded).unwrap();
+        assert_eq!(decoded, msg);
+    }
+}
+
+#[test]
+fn matches_serde_osc() {
+    for msg in messages() {
+        let encoded = encode(&msg).unwrap();
+        // serde_osc additionally prefixes the packet with its length.
+        let reference = serde_osc::to_vec(&msg).unwrap();
+        assert_eq!(&reference[4..], &encoded[..]);
+        let decoded: MsgRoot = serde_osc::from_slice(&reference).unwrap();
+        assert_eq!(decoded, msg);
+    }
+}
+
+#[test]
+fn pattern() {
+    let encoded = encode(&("/{left,empty}", (1i32, 2.0f32))).unwrap();
+    let parsed: OscMatches<MsgRoot> = decode(&encoded).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Left((), (1, 2.0))]);
+}
@@ -0,0 +1,68 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+extern crate serde_osc;
+use osc_address::{decode, encode, OscAddress, OscMatches, OscMessage, OscRawArgs, OscValue};
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    #[osc_address(address="known")]
+    Known((), (i32,)),
+    #[osc_address(address="sub")]
+    Sub((), MsgSub),
+    #[osc_address(fallback)]
+    Unknown(String, OscRawArgs),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgSub {
+    #[osc_address(address="a")]
+    A((), ()),
+    #[osc_address(fallback)]
+    Other(OscAddress, OscRawArgs),
+}
+
+fn raw_args() -> OscRawArgs {
+    OscRawArgs(vec![OscValue::Int(1), OscValue::String("x".to_owned())])
+}
+
+#[test]
+fn unknown() {
+    let packet = encode(&("/foo/bar", (1, "x"))).unwrap();
+    let msg: MsgRoot = decode(&packet).unwrap();
+    assert_eq!(msg, MsgRoot::Unknown("/foo/bar".to_owned(), raw_args()));
+    // The captured message can be forwarded unchanged.
+    assert_eq!(encode(&msg).unwrap(), packet);
+    // Known messages are unaffected.
+    let msg: MsgRoot = decode(&encode(&("/known", (5,))).unwrap()).unwrap();
+    assert_eq!(msg, MsgRoot::Known((), (5,)));
+}
+
+#[test]
+fn nested() {
+    let msg: MsgRoot = decode(&encode(&("/sub/zzz", (1, "x"))).unwrap()).unwrap();
+    let address = OscAddress::new("/zzz").unwrap();
+    assert_eq!(msg, MsgRoot::Sub((), MsgSub::Other(address, raw_args())));
+    assert_eq!(msg.get_address().unwrap(), "/sub/zzz");
+    // A message addressed to the nested enum itself.
+    let msg: MsgRoot = decode(&encode(&("/sub", ())).unwrap()).unwrap();
+    assert_eq!(msg, MsgRoot::Sub((), MsgSub::Other(OscAddress::root(), OscRawArgs::default())));
+    assert_eq!(msg.get_address().unwrap(), "/sub");
+}
+
+#[test]
+fn serde_osc() {
+    let packet = serde_osc::to_vec(&("/foo", (1, "x"))).unwrap();
+    let msg: MsgRoot = serde_osc::from_slice(&packet).unwrap();
+    assert_eq!(msg, MsgRoot::Unknown("/foo".to_owned(), raw_args()));
+}
+
+#[test]
+fn pattern() {
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/k*", (5,))).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Known((), (5,))]);
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/f*/bar", (1, "x"))).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Unknown("/f*/bar".to_owned(), raw_args())]);
+}
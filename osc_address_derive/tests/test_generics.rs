@@ -0,0 +1,85 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+use std::borrow::Cow;
+use std::fmt::Debug;
+use osc_address::{decode, encode, OscMatches, OscMessage};
+
+/// A reusable per-channel namespace, parameterised over its payload.
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum Channel<P> {
+    #[osc_address(address="ch{}")]
+    Strip(u8, P),
+    #[osc_address(address="master")]
+    Master((), P),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum Strip<T, I = u32> where T: Debug {
+    #[osc_address(address="gain")]
+    Gain((), (T,)),
+    Send(I, (T, T)),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum Label<'a> {
+    #[osc_address(address="label")]
+    Set { text: Cow<'a, str> },
+}
+
+#[derive(Serialize, Deserialize, OscMessage)]
+#[derive(Debug, PartialEq)]
+struct Pair<T> {
+    first: T,
+    second: T,
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum Root {
+    #[osc_address(address="mixer")]
+    Mixer((), Channel<Strip<f32>>),
+    #[osc_address(address="labels")]
+    Labels((), Channel<Label<'static>>),
+    #[osc_address(address="pair")]
+    Pair((), Pair<i32>),
+}
+
+fn messages() -> Vec<Root> {
+    vec![
+        Root::Mixer((), Channel::Strip(3, Strip::Gain((), (0.5,)))),
+        Root::Mixer((), Channel::Master((), Strip::Send(2, (0.25, 1.0)))),
+        Root::Labels((), Channel::Strip(1, Label::Set{ text: Cow::Borrowed("kick") })),
+        Root::Pair((), Pair{ first: 1, second: 2 }),
+    ]
+}
+
+#[test]
+fn address() {
+    let addresses = messages().iter().map(|msg| msg.get_address().unwrap()).collect::<Vec<_>>();
+    assert_eq!(addresses, vec!["/mixer/ch3/gain", "/mixer/master/2", "/labels/ch1/label", "/pair"]);
+}
+
+#[test]
+fn round_trip() {
+    for msg in messages() {
+        let decoded: Root = decode(&encode(&msg).unwrap()).unwrap();
+        assert_eq!(decoded, msg);
+    }
+    // Generic types may also be used at the top level.
+    let msg: Channel<Strip<i32, String>> = Channel::Strip(1, Strip::Send("fx".to_owned(), (1, 2)));
+    let decoded: Channel<Strip<i32, String>> = decode(&encode(&msg).unwrap()).unwrap();
+    assert_eq!(decoded, msg);
+}
+
+#[test]
+fn pattern() {
+    let parsed: OscMatches<Root> = decode(&encode(&("/mixer/*/gain", (0.5f32,))).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![Root::Mixer((), Channel::Master((), Strip::Gain((), (0.5,))))]);
+}
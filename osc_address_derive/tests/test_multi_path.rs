@@ -0,0 +1,74 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+use osc_address::{decode, encode, OscMatches, OscMessage};
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    #[osc_address(address="grid/{}/{}/press")]
+    Press((u8, u8), (i32,)),
+    #[osc_address(address="grid/{}/{}/led")]
+    Led((u8, u8), (f32,)),
+    #[osc_address(address="track/{}/fx{}")]
+    Effect((String, u32), MsgEffect),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgEffect {
+    #[osc_address(address="bypass")]
+    Bypass((), (i32,)),
+}
+
+fn messages() -> Vec<MsgRoot> {
+    vec![
+        MsgRoot::Press((3, 4), (1,)),
+        MsgRoot::Led((0, 15), (0.5,)),
+        MsgRoot::Effect(("drums".to_owned(), 2), MsgEffect::Bypass((), (1,))),
+    ]
+}
+
+#[test]
+fn address() {
+    let addresses = messages().iter().map(|msg| msg.get_address().unwrap()).collect::<Vec<_>>();
+    assert_eq!(addresses, vec!["/grid/3/4/press", "/grid/0/15/led", "/track/drums/fx2/bypass"]);
+}
+
+#[test]
+fn fields_in_component_order() {
+    // Each placeholder binds to the next element of the path argument, in
+    // order, whichever component it's in.
+    let msg: MsgRoot = decode(&encode(&("/grid/7/2/press", (1,))).unwrap()).unwrap();
+    assert_eq!(msg, MsgRoot::Press((7, 2), (1,)));
+    let msg: MsgRoot = decode(&encode(&("/grid/2/7/led", (0.5f32,))).unwrap()).unwrap();
+    assert_eq!(msg, MsgRoot::Led((2, 7), (0.5,)));
+}
+
+#[test]
+fn remainder_to_nested() {
+    // Only the components after the template are routed to the nested message.
+    let msg: MsgRoot = decode(&encode(&("/track/fx3/fx10/bypass", (1,))).unwrap()).unwrap();
+    assert_eq!(msg, MsgRoot::Effect(("fx3".to_owned(), 10), MsgEffect::Bypass((), (1,))));
+    assert!(decode::<MsgRoot>(&encode(&("/track/drums/fx2", (1,))).unwrap()).is_err());
+}
+
+#[test]
+fn mismatched() {
+    // A placeholder never spans a '/', so the number of components must match exactly.
+    for address in ["/grid/3/press", "/grid/3/4/5/press", "/grid/3/4", "/grid/3/4/press/more", "/track/drums/bypass"].iter() {
+        assert!(decode::<MsgRoot>(&encode(&(address, (1,))).unwrap()).is_err(), "{}", address);
+    }
+    // Each field must parse as its own type.
+    assert!(decode::<MsgRoot>(&encode(&("/grid/3/x/press", (1,))).unwrap()).is_err());
+    assert!(decode::<MsgRoot>(&encode(&("/grid/3/256/press", (1,))).unwrap()).is_err());
+}
+
+#[test]
+fn pattern() {
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/g*/3/4/{press,foo}", (1,))).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Press((3, 4), (1,))]);
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/track/*/fx2/bypass", (1,))).unwrap()).unwrap();
+    let path_arg = ("*".to_owned(), 2);
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Effect(path_arg, MsgEffect::Bypass((), (1,)))]);
+}
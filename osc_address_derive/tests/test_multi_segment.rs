@@ -0,0 +1,62 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+use osc_address::{decode, encode, OscMatches, OscMessage};
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    #[osc_address(address="mixer/master/volume")]
+    MasterVolume((), (f32,)),
+    #[osc_address(address="mixer/master")]
+    Master((), MsgMaster),
+    #[osc_address(address="mixer")]
+    Mixer((), (i32,)),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgMaster {
+    #[osc_address(address="mute")]
+    Mute((), (bool,)),
+    #[osc_address(address="eq/low/gain")]
+    LowGain((), (f32,)),
+}
+
+fn messages() -> Vec<MsgRoot> {
+    vec![
+        MsgRoot::MasterVolume((), (0.5,)),
+        MsgRoot::Master((), MsgMaster::Mute((), (true,))),
+        MsgRoot::Master((), MsgMaster::LowGain((), (-3.0,))),
+        MsgRoot::Mixer((), (1,)),
+    ]
+}
+
+#[test]
+fn address() {
+    let addresses: Vec<_> = messages().iter().map(|msg| msg.get_address().unwrap().into_string()).collect();
+    assert_eq!(addresses, vec!["/mixer/master/volume", "/mixer/master/mute", "/mixer/master/eq/low/gain", "/mixer"]);
+}
+
+#[test]
+fn round_trip() {
+    for msg in messages() {
+        let decoded: MsgRoot = decode(&encode(&msg).unwrap()).unwrap();
+        assert_eq!(decoded, msg);
+    }
+}
+
+#[test]
+fn partial_component() {
+    // "/mixer/masterful" must not match the literal "mixer/master".
+    assert!(decode::<MsgRoot>(&encode(&("/mixer/masterful/mute", (true,))).unwrap()).is_err());
+    assert!(decode::<MsgRoot>(&encode(&("/mixer/master/eq/low", (1.0f32,))).unwrap()).is_err());
+}
+
+#[test]
+fn pattern() {
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/mixer/*/{volume,mute}", (true,))).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Master((), MsgMaster::Mute((), (true,)))]);
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/mixer/master/eq/*/gain", (2.0f32,))).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Master((), MsgMaster::LowGain((), (2.0,)))]);
+}
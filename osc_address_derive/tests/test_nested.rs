@@ -22,6 +22,6 @@ enum MsgRoot {
 #[test]
 fn nested() {
     let msg = MsgRoot::Left((), MsgLeaf::Second((), (-1i32, 0f32)));
-    assert_eq!(msg.get_address(), "/left/second");
+    assert_eq!(msg.get_address().unwrap(), "/left/second");
 }
 
@@ -16,7 +16,7 @@ enum MyStruct {
 #[test]
 fn path() {
     let msg = MyStruct::Second((), (0i32, 1f32));
-    assert_eq!(msg.get_address(), "/second");
+    assert_eq!(msg.get_address().unwrap(), "/second");
 }
 
 #[test]
@@ -0,0 +1,94 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+use osc_address::{decode, encode};
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    // Literal routes declared before a path argument are tried first.
+    #[osc_address(address="42")]
+    Answer((), (f32,)),
+    ById(u32, (f32,)),
+    // Deliberately shadowed: "/7" is always routed to ById.
+    #[osc_address(address="7", allow_overlap)]
+    Seven((), (f32,)),
+    // Doesn't parse as a u32, so isn't shadowed.
+    #[osc_address(address="all")]
+    All((), (f32,)),
+    #[osc_address(address="ch{}")]
+    Channel(u8, (f32,)),
+    // Out of the range of the u8 placeholder, so isn't shadowed either.
+    #[osc_address(address="ch300")]
+    Overflow((), (f32,)),
+    // A nested message shadows only the addresses below it.
+    #[osc_address(address="mixer")]
+    Mixer((), MsgMixer),
+    #[osc_address(address="mixers/mute")]
+    MixersMute((), (f32,)),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgMixer {
+    #[osc_address(address="mute")]
+    Mute((), (f32,)),
+}
+
+#[test]
+fn declaration_order() {
+    let cases = [
+        ("/42", MsgRoot::Answer((), (0.5,))),
+        ("/3", MsgRoot::ById(3, (0.5,))),
+        ("/7", MsgRoot::ById(7, (0.5,))),
+        ("/all", MsgRoot::All((), (0.5,))),
+        ("/ch3", MsgRoot::Channel(3, (0.5,))),
+        ("/ch300", MsgRoot::Overflow((), (0.5,))),
+        ("/mixer/mute", MsgRoot::Mixer((), MsgMixer::Mute((), (0.5,)))),
+        ("/mixers/mute", MsgRoot::MixersMute((), (0.5,))),
+    ];
+    for &(address, ref expected) in cases.iter() {
+        let msg: MsgRoot = decode(&encode(&(address, (0.5f32,))).unwrap()).unwrap();
+        assert_eq!(msg, *expected, "{}", address);
+    }
+}
+
+mod tags {
+    use std::fmt;
+    use std::str::FromStr;
+
+    /// A tag which, despite its name, is no integer: it never parses from digits.
+    #[allow(non_camel_case_types)]
+    #[derive(Debug, PartialEq)]
+    pub struct u32(pub String);
+
+    impl FromStr for u32 {
+        type Err = ();
+        fn from_str(s: &str) -> Result<Self, ()> {
+            if s.chars().any(|c| c.is_ascii_digit()) { Err(()) } else { Ok(u32(s.to_owned())) }
+        }
+    }
+
+    impl fmt::Display for u32 {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str(&self.0)
+        }
+    }
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgTagged {
+    // Only a primitive named by its plain name or full path is taken to parse "42".
+    ByTag(tags::u32, (f32,)),
+    #[osc_address(address="42")]
+    Answer((), (f32,)),
+}
+
+#[test]
+fn path_argument_named_like_a_primitive() {
+    let msg: MsgTagged = decode(&encode(&("/42", (0.5f32,))).unwrap()).unwrap();
+    assert_eq!(msg, MsgTagged::Answer((), (0.5,)));
+    let msg: MsgTagged = decode(&encode(&("/bass", (0.5f32,))).unwrap()).unwrap();
+    assert_eq!(msg, MsgTagged::ByTag(tags::u32("bass".to_owned()), (0.5,)));
+}
@@ -0,0 +1,88 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+use osc_address::{decode, encode, OscMatches, OscMessage, OscPathArg};
+
+#[derive(OscPathArg)]
+#[derive(Debug, PartialEq)]
+#[osc_address(rename_all="lowercase")]
+enum Module {
+    Osc,
+    Filter,
+    #[osc_address(rename="adsr")]
+    Env,
+}
+
+#[derive(OscPathArg)]
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Sine,
+    Square,
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgSynth {
+    #[osc_address(address="shape")]
+    Shape((), (i32,)),
+    Module(Module, MsgModule),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgModule {
+    #[osc_address(address="gain")]
+    Gain((), (f32,)),
+    Wave(Shape, MsgWave),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgWave {
+    #[osc_address(address="on")]
+    On,
+}
+
+#[test]
+fn from_str_display() {
+    assert_eq!(Module::NAMES, &["osc", "filter", "adsr"]);
+    assert_eq!(Shape::NAMES, &["Sine", "Square"]);
+    assert_eq!("filter".parse::<Module>(), Ok(Module::Filter));
+    assert_eq!("adsr".parse::<Module>(), Ok(Module::Env));
+    assert_eq!("Env".parse::<Module>().unwrap_err().name(), "Env");
+    assert_eq!(Module::Env.to_string(), "adsr");
+    assert_eq!(format!("{:>6}", Module::Osc), "   osc");
+}
+
+#[test]
+fn round_trip() {
+    let messages = vec![
+        MsgSynth::Module(Module::Filter, MsgModule::Gain((), (0.5,))),
+        MsgSynth::Module(Module::Env, MsgModule::Wave(Shape::Square, MsgWave::On)),
+        MsgSynth::Shape((), (1,)),
+    ];
+    let addresses = messages.iter().map(|msg| msg.get_address().unwrap()).collect::<Vec<_>>();
+    assert_eq!(addresses, vec!["/filter/gain", "/adsr/Square/on", "/shape"]);
+    for msg in messages {
+        let decoded: MsgSynth = decode(&encode(&msg).unwrap()).unwrap();
+        assert_eq!(decoded, msg);
+    }
+    assert!(decode::<MsgSynth>(&encode(&("/lfo/gain", (0.5f32,))).unwrap()).is_err());
+}
+
+#[test]
+fn pattern() {
+    // Each value matched by the pattern yields a message.
+    let packet = encode(&("/{osc,adsr}/gain", (0.5f32,))).unwrap();
+    let parsed: OscMatches<MsgSynth> = decode(&packet).unwrap();
+    assert_eq!(parsed.into_messages(), vec![
+        MsgSynth::Module(Module::Osc, MsgModule::Gain((), (0.5,))),
+        MsgSynth::Module(Module::Env, MsgModule::Gain((), (0.5,))),
+    ]);
+    let packet = encode(&("/*/S*/on", ())).unwrap();
+    let parsed: OscMatches<MsgSynth> = decode(&packet).unwrap();
+    assert_eq!(parsed.into_messages().len(), 6);
+    let packet = encode(&("/f*/Sq*/on", ())).unwrap();
+    let parsed: OscMatches<MsgSynth> = decode(&packet).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgSynth::Module(Module::Filter, MsgModule::Wave(Shape::Square, MsgWave::On))]);
+}
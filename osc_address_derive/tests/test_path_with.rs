@@ -0,0 +1,83 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+use std::fmt;
+use std::num::ParseIntError;
+use osc_address::{decode, encode, OscMatches, OscMessage};
+
+/// A type without `FromStr` nor `Display`, as if from a foreign crate.
+#[derive(Debug, PartialEq)]
+struct NodeId(u16);
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    #[osc_address(address="track/{}", with="zero_padded")]
+    Track(u32, (f32,)),
+    #[osc_address(parse_with="parse_node", format_with="format_node")]
+    Node(NodeId, (i32,)),
+    // Only one side may be customized.
+    #[osc_address(address="bank{}", format_with="format_bank")]
+    Bank(u8, (i32,)),
+    #[osc_address(address="clip{}", with="zero_padded")]
+    Clip { #[osc_address(path)] id: u32, gain: f32 },
+}
+
+mod zero_padded {
+    use std::fmt;
+    use std::num::ParseIntError;
+    pub fn parse(text: &str) -> Result<u32, ParseIntError> {
+        text.parse()
+    }
+    pub fn format(index: &u32, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:03}", index)
+    }
+}
+
+fn parse_node(text: &str) -> Result<NodeId, ParseIntError> {
+    u16::from_str_radix(text.trim_start_matches("0x"), 16).map(NodeId)
+}
+
+fn format_node(node: &NodeId, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "0x{:04x}", node.0)
+}
+
+fn format_bank(bank: &u8, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{:02}", bank)
+}
+
+fn messages() -> Vec<MsgRoot> {
+    vec![
+        MsgRoot::Track(7, (0.5,)),
+        MsgRoot::Node(NodeId(0xbeef), (1,)),
+        MsgRoot::Bank(3, (2,)),
+        MsgRoot::Clip{ id: 12, gain: 0.5 },
+    ]
+}
+
+#[test]
+fn address() {
+    let addresses = messages().iter().map(|msg| msg.get_address().unwrap()).collect::<Vec<_>>();
+    assert_eq!(addresses, vec!["/track/007", "/0xbeef", "/bank03", "/clip012"]);
+}
+
+#[test]
+fn round_trip() {
+    for msg in messages() {
+        let decoded: MsgRoot = decode(&encode(&msg).unwrap()).unwrap();
+        assert_eq!(decoded, msg);
+    }
+    // The parse function accepts spellings which the format function never produces.
+    let msg: MsgRoot = decode(&encode(&("/track/7", (0.5f32,))).unwrap()).unwrap();
+    assert_eq!(msg, MsgRoot::Track(7, (0.5,)));
+}
+
+#[test]
+fn pattern() {
+    let packet = encode(&("/0xbeef", (1,))).unwrap();
+    let parsed: OscMatches<MsgRoot> = decode(&packet).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Node(NodeId(0xbeef), (1,))]);
+    let packet = encode(&("/track/012", (0.5f32,))).unwrap();
+    let parsed: OscMatches<MsgRoot> = decode(&packet).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Track(12, (0.5,))]);
+}
@@ -9,12 +9,13 @@ enum MyStruct {
     #[osc_address(address="first")]
     First((), ()),
     Second(i32, ()),
+    Third(String, ()),
 }
 
 #[test]
 fn path() {
     let msg = MyStruct::Second(42, ());
-    assert_eq!(msg.get_address(), "/42");
+    assert_eq!(msg.get_address().unwrap(), "/42");
 }
 
 #[test]
@@ -24,3 +25,10 @@ fn serialize() {
     let expected: Vec<u8> = b"\0\0\0\x08/42\0,\0\0\0".iter().cloned().collect();
     assert_eq!(serialized, expected);
 }
+
+#[test]
+fn invalid_path_arg() {
+    let msg = MyStruct::Third("not/one/component".to_string(), ());
+    assert!(msg.get_address().is_err());
+    assert!(serde_osc::ser::to_vec(&msg).is_err());
+}
@@ -0,0 +1,115 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+extern crate serde_osc;
+use osc_address::OscMatches;
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    #[osc_address(address="routegraph")]
+    RouteGraph((), MsgRouteGraph),
+    #[osc_address(address="renderer")]
+    Renderer((), MsgRenderer),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRouteGraph {
+    #[osc_address(address="add_node")]
+    AddNode((), (i32,)),
+    #[osc_address(address="add_edge")]
+    AddEdge((), (i32, i32)),
+    #[osc_address(address="del_edge")]
+    DelEdge((), (i32, i32)),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRenderer {
+    ByName(String, MsgRendererByName),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRendererByName {
+    #[osc_address(address="say")]
+    Say((), (String,)),
+}
+
+/// Encode a null-terminated string, padded to a multiple of 4 bytes.
+fn osc_string(s: &str) -> Vec<u8> {
+    let mut encoded: Vec<u8> = s.bytes().collect();
+    // Room for at least one null terminator.
+    encoded.resize((s.len() + 4) / 4 * 4, 0);
+    encoded
+}
+
+/// Build an OSC packet addressed to `pattern`.
+fn packet(pattern: &str, type_tags: &str, args: &[u8]) -> Vec<u8> {
+    let mut contents = osc_string(pattern);
+    contents.extend(osc_string(type_tags));
+    contents.extend(args);
+    let mut packet = vec![0, 0, 0, contents.len() as u8];
+    packet.extend(contents);
+    packet
+}
+
+fn matches(pattern: &str, type_tags: &str, args: &[u8]) -> Vec<MsgRoot> {
+    let parsed: OscMatches<MsgRoot> = serde_osc::from_slice(&packet(pattern, type_tags, args)).unwrap();
+    parsed.into_messages()
+}
+
+#[test]
+fn literal() {
+    let parsed = matches("/routegraph/add_node", ",i", b"\0\0\0\x01");
+    assert_eq!(parsed, vec![MsgRoot::RouteGraph((), MsgRouteGraph::AddNode((), (1,)))]);
+}
+
+#[test]
+fn alternatives() {
+    let parsed = matches("/routegraph/{add_edge,del_edge}", ",ii", b"\0\0\0\x01\0\0\0\x02");
+    assert_eq!(parsed, vec![
+        MsgRoot::RouteGraph((), MsgRouteGraph::AddEdge((), (1, 2))),
+        MsgRoot::RouteGraph((), MsgRouteGraph::DelEdge((), (1, 2))),
+    ]);
+}
+
+#[test]
+fn wildcard_skips_mismatched_payloads() {
+    // add_node takes only one argument, so only the edge messages are produced.
+    let parsed = matches("/*/[a-d]??_*", ",ii", b"\0\0\0\x01\0\0\0\x02");
+    assert_eq!(parsed, vec![
+        MsgRoot::RouteGraph((), MsgRouteGraph::AddEdge((), (1, 2))),
+        MsgRoot::RouteGraph((), MsgRouteGraph::DelEdge((), (1, 2))),
+    ]);
+}
+
+#[test]
+fn raw_path_arg() {
+    let parsed = matches("/renderer/*/say", ",s", b"hello\0\0\0");
+    assert_eq!(parsed, vec![
+        MsgRoot::Renderer((), MsgRenderer::ByName("*".to_string(), MsgRendererByName::Say((), ("hello".to_string(),)))),
+    ]);
+}
+
+#[test]
+fn no_match() {
+    let parsed: Result<OscMatches<MsgRoot>, _> = serde_osc::from_slice(&packet("/mixer/*", ",i", b"\0\0\0\x01"));
+    assert!(parsed.is_err());
+}
+
+#[test]
+fn malformed_pattern() {
+    let parsed: Result<OscMatches<MsgRoot>, _> = serde_osc::from_slice(&packet("/routegraph/{add_node", ",i", b"\0\0\0\x01"));
+    assert!(parsed.is_err());
+}
+
+#[test]
+fn many_wildcards() {
+    // A pattern with many '*' is routed in time linear in the component length.
+    let parsed: Result<OscMatches<MsgRoot>, _> = serde_osc::from_slice(&packet("/*o*u*t*e*g*r*a*p*h*e*s/add_node", ",i", b"\0\0\0\x01"));
+    assert!(parsed.is_err());
+    let parsed = matches("/*o*u*t*e*g*r*a*p*h*/add_node", ",i", b"\0\0\0\x01");
+    assert_eq!(parsed, vec![MsgRoot::RouteGraph((), MsgRouteGraph::AddNode((), (1,)))]);
+}
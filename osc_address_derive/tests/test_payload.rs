@@ -1,30 +1,75 @@
 #[macro_use]
-extern crate serde_derive;
-#[macro_use]
 extern crate osc_address_derive;
-extern crate serde_osc;
+extern crate osc_address;
+#[macro_use]
+extern crate serde_derive;
+use osc_address::{decode, encode, OscMatches};
+
+type Args = (i32, f32);
+type Sub = MsgSub;
+
+/// A payload struct which is not itself an OscMessage.
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, PartialEq)]
+struct ControlArgs {
+    id: i32,
+    amplitude: f32,
+}
 
 #[derive(OscMessage)]
-enum MsgLeaf  {
-    #[osc_address(address="first")]
-    First((), MsgData),
-    #[osc_address(address="second")]
-    Second((), (i32, f32)),
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    #[osc_address(address="pair", payload="args")]
+    Pair((), Args),
+    #[osc_address(address="levels", payload="args")]
+    Levels((), Vec<f32>),
+    #[osc_address(address="control", payload="struct")]
+    Control((), ControlArgs),
+    #[osc_address(address="sub", payload="message")]
+    Sub(Sub),
+    // The inference still applies without a directive.
+    #[osc_address(address="plain")]
+    Plain((), (i32,)),
 }
 
 #[derive(OscMessage)]
-#[derive(Serialize, Deserialize)]
-struct MsgData {
-    v: i32,
-    s: String,
+#[derive(Debug, PartialEq)]
+enum MsgSub {
+    #[osc_address(address="go")]
+    Go((), (i32,)),
+}
+
+fn messages() -> Vec<MsgRoot> {
+    vec![
+        MsgRoot::Pair((), (1, 0.5)),
+        MsgRoot::Levels((), vec![0.25, 0.5, 1.0]),
+        MsgRoot::Control((), ControlArgs{ id: 3, amplitude: 0.5 }),
+        MsgRoot::Sub(MsgSub::Go((), (2,))),
+        MsgRoot::Plain((), (4,)),
+    ]
+}
+
+#[test]
+fn encoding() {
+    assert_eq!(encode(&MsgRoot::Pair((), (1, 0.5))).unwrap(), encode(&("/pair", (1, 0.5f32))).unwrap());
+    assert_eq!(encode(&MsgRoot::Levels((), vec![0.25, 0.5])).unwrap(), encode(&("/levels", (0.25f32, 0.5f32))).unwrap());
+    assert_eq!(encode(&MsgRoot::Control((), ControlArgs{ id: 3, amplitude: 0.5 })).unwrap(), encode(&("/control", (3, 0.5f32))).unwrap());
 }
 
 #[test]
-fn test_payload() {
-    let payload = MsgData{ v: 0x01020304, s: "test".to_string() };
-    let msg = MsgLeaf::First((), payload);
-    let serialized = serde_osc::ser::to_vec(&msg).unwrap();
-    let expected: Vec<u8> = b"\0\0\0\x18/first\0\0,is\0\x01\x02\x03\x04test\0\0\0\0".iter().cloned().collect();
-    assert_eq!(serialized, expected);
+fn round_trip() {
+    for msg in messages() {
+        let decoded: MsgRoot = decode(&encode(&msg).unwrap()).unwrap();
+        assert_eq!(decoded, msg);
+    }
 }
 
+#[test]
+fn pattern() {
+    let packet = encode(&("/{pair,control}", (1, 0.5f32))).unwrap();
+    let parsed: OscMatches<MsgRoot> = decode(&packet).unwrap();
+    assert_eq!(parsed.into_messages(), vec![
+        MsgRoot::Pair((), (1, 0.5)),
+        MsgRoot::Control((), ControlArgs{ id: 1, amplitude: 0.5 }),
+    ]);
+}
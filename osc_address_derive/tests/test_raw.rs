@@ -0,0 +1,71 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+extern crate serde_osc;
+use osc_address::{decode, encode, AbsOscTime, OscMatches, OscMessage, OscRawMessage, OscValue};
+use osc_address::{OscColor, OscMidi, OscSymbol, OscTimeTag};
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    #[osc_address(address="note")]
+    Note((), (OscMidi, OscSymbol, Vec<i32>)),
+    #[osc_address(address="misc")]
+    Misc((), (String, f64, Option<i32>, OscColor, OscTimeTag)),
+}
+
+fn note() -> MsgRoot {
+    MsgRoot::Note((), (OscMidi::new(1, 0x90, 60, 100), OscSymbol::from("sym"), vec![1, 2]))
+}
+
+fn misc() -> MsgRoot {
+    MsgRoot::Misc((), ("str".to_owned(), 0.5, None, OscColor::new(1, 2, 3, 4), OscTimeTag(AbsOscTime::new(7, 8))))
+}
+
+#[test]
+fn to_raw() {
+    let raw = note().to_raw().unwrap();
+    assert_eq!(raw, OscRawMessage::new("/note", vec![
+        OscValue::Midi(OscMidi::new(1, 0x90, 60, 100)),
+        OscValue::Symbol("sym".to_owned()),
+        OscValue::Array(vec![OscValue::Int(1), OscValue::Int(2)]),
+    ]));
+    let raw = misc().to_raw().unwrap();
+    let tags: String = raw.args.iter().map(OscValue::type_tag).collect();
+    assert_eq!(tags, "sdNrt");
+}
+
+#[test]
+fn try_from_raw() {
+    for msg in [note(), misc()].iter() {
+        let raw = msg.to_raw().unwrap();
+        assert_eq!(MsgRoot::try_from_raw(&raw).unwrap(), *msg);
+        // The raw message encodes exactly like the typed one.
+        assert_eq!(encode(&raw).unwrap(), encode(msg).unwrap());
+    }
+    let unknown = OscRawMessage::new("/unknown", vec![]);
+    assert!(MsgRoot::try_from_raw(&unknown).is_err());
+    let mismatched = OscRawMessage::new("/note", vec![OscValue::Int(1)]);
+    assert!(MsgRoot::try_from_raw(&mismatched).is_err());
+}
+
+#[test]
+fn decode_raw() {
+    let packet = encode(&note()).unwrap();
+    let raw: OscRawMessage = decode(&packet).unwrap();
+    assert_eq!(raw, note().to_raw().unwrap());
+    // Other serde backends work too, with the closest OSC type for each argument.
+    let packet = serde_osc::to_vec(&("/x", ("str", 3, 0.5f32))).unwrap();
+    let raw: OscRawMessage = serde_osc::from_slice(&packet).unwrap();
+    assert_eq!(raw, OscRawMessage::new("/x", vec![OscValue::String("str".to_owned()), OscValue::Int(3), OscValue::Float(0.5)]));
+}
+
+#[test]
+fn pattern() {
+    // Raw messages keep the pattern as their address.
+    let packet = encode(&OscRawMessage::new("/n*", vec![OscValue::Impulse])).unwrap();
+    let parsed: OscMatches<OscRawMessage> = decode(&packet).unwrap();
+    assert_eq!(parsed.into_messages(), vec![OscRawMessage::new("/n*", vec![OscValue::Impulse])]);
+    let raw = note().to_raw().unwrap();
+    assert_eq!(raw.get_address().unwrap(), "/note");
+}
@@ -0,0 +1,79 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+use osc_address::{decode, encode, OscMatches, OscMessage};
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+#[osc_address(rename_all="snake_case")]
+enum MsgRoot {
+    AddNode((), (i32,)),
+    Reset,
+    Renderer((), MsgRenderer),
+    // An explicit address takes precedence.
+    #[osc_address(address="del")]
+    DelNode((), (i32,)),
+    // As do path arguments.
+    ById(u32, (String,)),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+#[osc_address(rename_all="kebab-case")]
+enum MsgRenderer {
+    SetVolume((f32,)),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+#[osc_address(rename_all="camelCase")]
+enum MsgCamel {
+    SetVolume((f32,)),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+#[osc_address(rename_all="SCREAMING_SNAKE_CASE")]
+enum MsgScreaming {
+    SetVolume((f32,)),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+#[osc_address(rename_all="lowercase")]
+enum MsgLower {
+    SetVolume((f32,)),
+}
+
+fn messages() -> Vec<MsgRoot> {
+    vec![
+        MsgRoot::AddNode((), (1,)),
+        MsgRoot::Reset,
+        MsgRoot::Renderer((), MsgRenderer::SetVolume((0.5,))),
+        MsgRoot::DelNode((), (2,)),
+        MsgRoot::ById(3, ("x".to_owned(),)),
+    ]
+}
+
+#[test]
+fn address() {
+    let addresses = messages().iter().map(|msg| msg.get_address().unwrap()).collect::<Vec<_>>();
+    assert_eq!(addresses, vec!["/add_node", "/reset", "/renderer/set-volume", "/del", "/3"]);
+    assert_eq!(MsgCamel::SetVolume((0.5,)).get_address().unwrap(), "/setVolume");
+    assert_eq!(MsgScreaming::SetVolume((0.5,)).get_address().unwrap(), "/SET_VOLUME");
+    assert_eq!(MsgLower::SetVolume((0.5,)).get_address().unwrap(), "/setvolume");
+}
+
+#[test]
+fn round_trip() {
+    for msg in messages() {
+        let decoded: MsgRoot = decode(&encode(&msg).unwrap()).unwrap();
+        assert_eq!(decoded, msg);
+    }
+}
+
+#[test]
+fn pattern() {
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/add_*", (1,))).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::AddNode((), (1,))]);
+}
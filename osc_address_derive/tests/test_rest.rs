@@ -0,0 +1,88 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+extern crate serde;
+use osc_address::{decode, encode, OscAddress, OscMatches, OscMessage, OscRawArgs, OscValue};
+use serde::Deserialize;
+use serde::de::value::{Error, SeqDeserializer};
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    #[osc_address(address="status")]
+    Status((), ()),
+    #[osc_address(address="plugin/{}")]
+    Plugin { #[osc_address(path)] id: u32, #[osc_address(rest)] path: OscAddress, value: f32 },
+    #[osc_address(address="host", alias="h")]
+    Host(#[osc_address(rest)] OscAddress, OscRawArgs),
+    #[osc_address(address="proxy")]
+    Proxy((), MsgProxy<(i32,)>),
+}
+
+/// Forwards everything which reaches it, with a payload of the given type.
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgProxy<P> {
+    Forward(#[osc_address(rest)] String, P),
+}
+
+fn raw_args() -> OscRawArgs {
+    OscRawArgs(vec![OscValue::Int(1), OscValue::String("x".to_owned())])
+}
+
+fn address(address: &str) -> OscAddress {
+    OscAddress::new(address).unwrap()
+}
+
+#[test]
+fn capture() {
+    let msg: MsgRoot = decode(&encode(&("/plugin/3/filter/cutoff", (0.5f32,))).unwrap()).unwrap();
+    assert_eq!(msg, MsgRoot::Plugin{ id: 3, path: address("/filter/cutoff"), value: 0.5 });
+    let msg: MsgRoot = decode(&encode(&("/h/a/b", (1, "x"))).unwrap()).unwrap();
+    assert_eq!(msg, MsgRoot::Host(address("/a/b"), raw_args()));
+    let msg: MsgRoot = decode(&encode(&("/proxy/x/y", (7,))).unwrap()).unwrap();
+    assert_eq!(msg, MsgRoot::Proxy((), MsgProxy::Forward("/x/y".to_owned(), (7,))));
+    // Nothing below the prefix is captured as the root address.
+    let msg: MsgRoot = decode(&encode(&("/host", ())).unwrap()).unwrap();
+    assert_eq!(msg, MsgRoot::Host(OscAddress::root(), OscRawArgs::default()));
+    let msg: MsgRoot = decode(&encode(&("/proxy", (7,))).unwrap()).unwrap();
+    assert_eq!(msg, MsgRoot::Proxy((), MsgProxy::Forward("/".to_owned(), (7,))));
+    // Only whole components match the prefix.
+    assert!(decode::<MsgRoot>(&encode(&("/hosts/a", ())).unwrap()).is_err());
+}
+
+#[test]
+fn round_trip() {
+    let messages = vec![
+        MsgRoot::Status((), ()),
+        MsgRoot::Plugin{ id: 3, path: address("/filter/cutoff"), value: 0.5 },
+        MsgRoot::Host(address("/a/b"), raw_args()),
+        MsgRoot::Host(OscAddress::root(), OscRawArgs::default()),
+        MsgRoot::Proxy((), MsgProxy::Forward("/x/y".to_owned(), (7,))),
+    ];
+    let addresses = messages.iter().map(|msg| msg.get_address().unwrap()).collect::<Vec<_>>();
+    assert_eq!(addresses, vec!["/status", "/plugin/3/filter/cutoff", "/host/a/b", "/host", "/proxy/x/y"]);
+    for msg in messages {
+        let decoded: MsgRoot = decode(&encode(&msg).unwrap()).unwrap();
+        assert_eq!(decoded, msg);
+    }
+}
+
+#[test]
+fn pattern() {
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/proxy/*/y", (7,))).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Proxy((), MsgProxy::Forward("/*/y".to_owned(), (7,)))]);
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/pro*", (7,))).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Proxy((), MsgProxy::Forward("/".to_owned(), (7,)))]);
+    // An OscAddress can't hold the wildcards of a pattern, so only literal remainders match.
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/h*/a", (1, "x"))).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Host(address("/a"), raw_args())]);
+    assert!(decode::<OscMatches<MsgRoot>>(&encode(&("/host/*", (1, "x"))).unwrap()).is_err());
+}
+
+#[test]
+fn missing_payload() {
+    // A message holding nothing but its address is rejected, rather than panicking.
+    let err = MsgRoot::deserialize(SeqDeserializer::<_, Error>::new(Some("/host/a").into_iter())).unwrap_err();
+    assert!(err.to_string().contains("invalid length 0"), "{}", err);
+}
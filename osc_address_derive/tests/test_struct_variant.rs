@@ -0,0 +1,71 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+extern crate serde_osc;
+use osc_address::{decode, encode, OscMatches, OscMessage};
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    #[osc_address(address="ch{}/gain")]
+    SetGain { #[osc_address(path)] channel: u8, gain: f32 },
+    #[osc_address(address="grid/{}/{}/press")]
+    Press { #[osc_address(path)] x: u8, state: i32, #[osc_address(path)] y: u8 },
+    #[osc_address(address="reset")]
+    Reset {},
+    #[osc_address(address="note")]
+    Note { pitch: i32, velocity: i32 },
+    #[osc_address(address="track")]
+    Track((), MsgTrack),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgTrack {
+    // Field names may coincide with those of the generated code.
+    Name { #[osc_address(path)] address: String, seq: String },
+}
+
+fn messages() -> Vec<MsgRoot> {
+    vec![
+        MsgRoot::SetGain{ channel: 3, gain: 0.5 },
+        MsgRoot::Press{ x: 1, state: 1, y: 2 },
+        MsgRoot::Reset{},
+        MsgRoot::Note{ pitch: 60, velocity: 100 },
+        MsgRoot::Track((), MsgTrack::Name{ address: "drums".to_owned(), seq: "kick".to_owned() }),
+    ]
+}
+
+#[test]
+fn address() {
+    let addresses = messages().iter().map(|msg| msg.get_address().unwrap()).collect::<Vec<_>>();
+    assert_eq!(addresses, vec!["/ch3/gain", "/grid/1/2/press", "/reset", "/note", "/track/drums"]);
+}
+
+#[test]
+fn args() {
+    // The remaining fields form the arguments, in declaration order.
+    assert_eq!(encode(&MsgRoot::Note{ pitch: 60, velocity: 100 }).unwrap(), encode(&("/note", (60, 100))).unwrap());
+    assert_eq!(encode(&MsgRoot::Reset{}).unwrap(), encode(&("/reset", ())).unwrap());
+}
+
+#[test]
+fn round_trip() {
+    for msg in messages() {
+        let decoded: MsgRoot = decode(&encode(&msg).unwrap()).unwrap();
+        assert_eq!(decoded, msg);
+    }
+}
+
+#[test]
+fn serde_osc() {
+    let packet = serde_osc::to_vec(&("/grid/4/5/press", (0,))).unwrap();
+    let msg: MsgRoot = serde_osc::from_slice(&packet).unwrap();
+    assert_eq!(msg, MsgRoot::Press{ x: 4, state: 0, y: 5 });
+}
+
+#[test]
+fn pattern() {
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/ch7/g*", (1.0f32,))).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::SetGain{ channel: 7, gain: 1.0 }]);
+}
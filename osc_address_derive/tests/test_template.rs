@@ -0,0 +1,66 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+use osc_address::{decode, encode, OscMatches, OscMessage};
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    #[osc_address(address="ch{}")]
+    Channel(u8, MsgChannel),
+    #[osc_address(address="fader.{}")]
+    Fader(u32, (f32,)),
+    #[osc_address(address="cell{}_{}")]
+    Cell((u8, u8), (i32,)),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgChannel {
+    #[osc_address(address="vol")]
+    Volume((), (f32,)),
+    #[osc_address(address="{}x")]
+    Mute(String, ()),
+}
+
+fn messages() -> Vec<MsgRoot> {
+    vec![
+        MsgRoot::Channel(12, MsgChannel::Volume((), (0.5,))),
+        MsgRoot::Channel(3, MsgChannel::Mute("on".to_owned(), ())),
+        MsgRoot::Fader(7, (1.0,)),
+        MsgRoot::Cell((3, 14), (1,)),
+    ]
+}
+
+#[test]
+fn address() {
+    let addresses = messages().iter().map(|msg| msg.get_address().unwrap()).collect::<Vec<_>>();
+    assert_eq!(addresses, vec!["/ch12/vol", "/ch3/onx", "/fader.7", "/cell3_14"]);
+}
+
+#[test]
+fn round_trip() {
+    for msg in messages() {
+        let decoded: MsgRoot = decode(&encode(&msg).unwrap()).unwrap();
+        assert_eq!(decoded, msg);
+    }
+}
+
+#[test]
+fn mismatched() {
+    for address in ["/ch/vol", "/chx/vol", "/fader7", "/fader.", "/cell3", "/cell_3", "/cell3_"].iter() {
+        assert!(decode::<MsgRoot>(&encode(&(address, (1.0f32,))).unwrap()).is_err(), "{}", address);
+    }
+    // The first placeholder ends at the first occurrence of the following literal.
+    let msg: MsgRoot = decode(&encode(&("/cell1_2", (5,))).unwrap()).unwrap();
+    assert_eq!(msg, MsgRoot::Cell((1, 2), (5,)));
+    assert!(decode::<MsgRoot>(&encode(&("/cell1_2_3", (5,))).unwrap()).is_err());
+}
+
+#[test]
+fn pattern() {
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/ch4/{vol,x}", (0.5f32,))).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Channel(4, MsgChannel::Volume((), (0.5,)))]);
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/cell2_5", (1,))).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Cell((2, 5), (1,))]);
+}
@@ -0,0 +1,69 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+use osc_address::{decode, encode, AbsOscTime, OscMatches, OscMessage};
+use osc_address::{OscBlob, OscChar, OscColor, OscImpulse, OscMidi, OscSymbol, OscTimeTag};
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    #[osc_address(address="flags")]
+    Flags((), (bool, bool, Option<i32>, Option<i32>, OscImpulse)),
+    #[osc_address(address="numbers")]
+    Numbers((), (i64, f64, OscChar, OscColor)),
+    #[osc_address(address="data")]
+    Data((), (OscMidi, OscTimeTag, OscSymbol, OscBlob)),
+}
+
+fn messages() -> Vec<MsgRoot> {
+    vec![
+        MsgRoot::Flags((), (true, false, None, Some(7), OscImpulse)),
+        MsgRoot::Numbers((), (-1 << 40, 0.125, OscChar('x'), OscColor::new(1, 2, 3, 4))),
+        MsgRoot::Data((), (OscMidi::new(0, 0x90, 60, 127), OscTimeTag(AbsOscTime::new(5, 6)),
+            OscSymbol::from("sym"), OscBlob(vec![9, 8, 7]))),
+    ]
+}
+
+#[test]
+fn type_tags() {
+    let messages = messages();
+    let flags = encode(&messages[0]).unwrap();
+    assert_eq!(flags, b"/flags\0\0,TFNiI\0\0\0\0\0\x07".to_vec());
+
+    let numbers = encode(&messages[1]).unwrap();
+    assert_eq!(&numbers[..20], b"/numbers\0\0\0\0,hdcr\0\0\0");
+    let args = &numbers[20..];
+    assert_eq!(&args[..8], &[0xff, 0xff, 0xff, 0, 0, 0, 0, 0]);
+    assert_eq!(&args[8..16], &0.125f64.to_be_bytes());
+    assert_eq!(&args[16..], &[0, 0, 0, b'x', 1, 2, 3, 4]);
+
+    let data = encode(&messages[2]).unwrap();
+    assert_eq!(&data[..16], b"/data\0\0\0,mtSb\0\0\0");
+    let args = &data[16..];
+    assert_eq!(&args[..4], &[0, 0x90, 60, 127]);
+    assert_eq!(&args[4..12], &[0, 0, 0, 5, 0, 0, 0, 6]);
+    assert_eq!(&args[12..16], b"sym\0");
+    assert_eq!(&args[16..], &[0, 0, 0, 3, 9, 8, 7, 0]);
+}
+
+#[test]
+fn round_trip() {
+    for msg in messages() {
+        let decoded: MsgRoot = decode(&encode(&msg).unwrap()).unwrap();
+        assert_eq!(decoded, msg);
+    }
+}
+
+#[test]
+fn pattern() {
+    // Arguments are replayed into each matched message, so they must survive
+    // being captured.
+    for msg in messages() {
+        let address = msg.get_address().unwrap();
+        let mut encoded = encode(&msg).unwrap();
+        // Replace the first letter of the address with a wildcard, keeping the length.
+        encoded[1] = b'*';
+        let parsed: OscMatches<MsgRoot> = decode(&encoded).unwrap();
+        assert_eq!(parsed.into_messages(), vec![msg], "{}", address);
+    }
+}
@@ -0,0 +1,61 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+extern crate serde_osc;
+use osc_address::{decode, encode, OscMatches, OscMessage};
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgRoot {
+    #[osc_address(address="reset")]
+    Reset,
+    #[osc_address(address="play")]
+    Play((i32, f32)),
+    #[osc_address(address="transport")]
+    Transport(MsgTransport),
+}
+
+#[derive(OscMessage)]
+#[derive(Debug, PartialEq)]
+enum MsgTransport {
+    #[osc_address(address="stop")]
+    Stop,
+}
+
+fn messages() -> Vec<MsgRoot> {
+    vec![
+        MsgRoot::Reset,
+        MsgRoot::Play((1, 0.5)),
+        MsgRoot::Transport(MsgTransport::Stop),
+    ]
+}
+
+#[test]
+fn encoding() {
+    assert_eq!(encode(&MsgRoot::Reset).unwrap(), b"/reset\0\0,\0\0\0");
+    assert_eq!(encode(&MsgRoot::Play((1, 0.5))).unwrap(), encode(&("/play", (1, 0.5f32))).unwrap());
+    assert_eq!(MsgRoot::Transport(MsgTransport::Stop).get_address().unwrap(), "/transport/stop");
+}
+
+#[test]
+fn round_trip() {
+    for msg in messages() {
+        let decoded: MsgRoot = decode(&encode(&msg).unwrap()).unwrap();
+        assert_eq!(decoded, msg);
+    }
+}
+
+#[test]
+fn serde_osc() {
+    let packet = serde_osc::to_vec(&MsgRoot::Reset).unwrap();
+    let msg: MsgRoot = serde_osc::from_slice(&packet).unwrap();
+    assert_eq!(msg, MsgRoot::Reset);
+}
+
+#[test]
+fn pattern() {
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/{reset,play}", ())).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Reset]);
+    let parsed: OscMatches<MsgRoot> = decode(&encode(&("/t*/s*", ())).unwrap()).unwrap();
+    assert_eq!(parsed.into_messages(), vec![MsgRoot::Transport(MsgTransport::Stop)]);
+}
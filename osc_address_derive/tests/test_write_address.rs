@@ -0,0 +1,71 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+use std::fmt;
+use std::str;
+use osc_address::{OscAddressError, OscMessage};
+
+#[derive(OscMessage)]
+enum MsgLeaf {
+    #[osc_address(address="gain")]
+    Gain((), (f32,)),
+}
+
+#[derive(OscMessage)]
+enum MsgRoot {
+    #[osc_address(address="mixer")]
+    Mixer((), MsgChannel),
+}
+
+#[derive(OscMessage)]
+enum MsgChannel {
+    Channel(u16, MsgLeaf),
+}
+
+/// Fixed-size buffer that lives on the stack.
+struct StackBuf {
+    buf: [u8; 16],
+    len: usize,
+}
+
+impl fmt::Write for StackBuf {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > self.buf.len() {
+            return Err(fmt::Error);
+        }
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+fn msg(channel: u16) -> MsgRoot {
+    MsgRoot::Mixer((), MsgChannel::Channel(channel, MsgLeaf::Gain((), (0.5,))))
+}
+
+#[test]
+fn write_to_reused_string() {
+    let mut address = String::new();
+    msg(3).write_address(&mut address).unwrap();
+    assert_eq!(address, "/mixer/3/gain");
+    address.clear();
+    msg(12).write_address(&mut address).unwrap();
+    assert_eq!(address, "/mixer/12/gain");
+}
+
+#[test]
+fn write_to_stack_buffer() {
+    let mut buf = StackBuf{ buf: [0; 16], len: 0 };
+    msg(3).write_address(&mut buf).unwrap();
+    assert_eq!(str::from_utf8(&buf.buf[..buf.len]).unwrap(), "/mixer/3/gain");
+    // "/mixer/65535/gain" doesn't fit in 16 bytes.
+    let mut buf = StackBuf{ buf: [0; 16], len: 0 };
+    assert_eq!(msg(65535).write_address(&mut buf), Err(OscAddressError::WriteFailed));
+}
+
+#[test]
+fn address_len() {
+    assert_eq!(msg(3).address_len(), "/mixer/3/gain".len());
+    assert_eq!(msg(65535).address_len(), "/mixer/65535/gain".len());
+}
@@ -0,0 +1,11 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+
+#[derive(OscMessage)]
+#[osc_address(rename_all="Title Case")]
+enum MsgRoot {
+    SetVolume((), (f32,)),
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+
+#[derive(OscMessage)]
+enum MsgRoot {
+    // Adjacent placeholders can't be told apart.
+    #[osc_address(address="cell{}{}")]
+    Cell((u8, u8), (i32,)),
+}
+
+fn main() {}
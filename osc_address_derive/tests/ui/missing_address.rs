@@ -0,0 +1,13 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+
+#[derive(OscMessage)]
+enum MsgRoot {
+    #[osc_address(address="reset")]
+    Reset((), ()),
+    // Without a path argument, nothing determines the address.
+    Volume((), (f32,)),
+}
+
+fn main() {}
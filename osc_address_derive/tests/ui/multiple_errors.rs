@@ -0,0 +1,18 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+
+#[derive(OscMessage)]
+enum MsgRoot {
+    #[osc_address(address="volume")]
+    #[osc_address(payload="nested")]
+    #[osc_address(parse_with=42)]
+    Volume((), (f32,)),
+    #[osc_address(address="pan")]
+    Pan((), (f32,)),
+    #[osc_address(address="mute")]
+    #[osc_address(payload="message")]
+    Mute((), (bool,)),
+}
+
+fn main() {}
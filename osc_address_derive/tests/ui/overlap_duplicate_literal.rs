@@ -0,0 +1,14 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+
+#[derive(OscMessage)]
+enum MsgRoot {
+    #[osc_address(address="foo")]
+    Foo((), (f32,)),
+    // "/foo" is always routed to Foo.
+    #[osc_address(address="foo")]
+    OtherFoo((), (i32,)),
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+
+#[derive(OscMessage)]
+enum MsgRoot {
+    ById(u32, (f32,)),
+    // "/42" parses as the u32 of ById, so it is always routed there.
+    #[osc_address(address="42")]
+    Answer((), (f32,)),
+}
+
+// The primitive type may also be named by its full path.
+#[derive(OscMessage)]
+enum MsgTrack {
+    ByIndex(::std::primitive::u16, (f32,)),
+    #[osc_address(address="7")]
+    Seventh((), (f32,)),
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+
+#[derive(OscMessage)]
+enum MsgRoot {
+    #[osc_address(address="mixer")]
+    Mixer((), MsgMixer),
+    // "/mixer/master/volume" is always routed to MsgMixer.
+    #[osc_address(address="mixer/master/volume")]
+    MasterVolume((), (f32,)),
+}
+
+#[derive(OscMessage)]
+enum MsgMixer {
+    #[osc_address(address="mute")]
+    Mute((), (i32,)),
+}
+
+fn main() {}
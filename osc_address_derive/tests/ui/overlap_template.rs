@@ -0,0 +1,14 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+
+#[derive(OscMessage)]
+enum MsgRoot {
+    #[osc_address(address="ch{}")]
+    Channel(u8, (f32,)),
+    // "/ch3" is always routed to Channel.
+    #[osc_address(address="ch3")]
+    Third((), (f32,)),
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+#[macro_use]
+extern crate osc_address_derive;
+extern crate osc_address;
+
+#[derive(OscMessage)]
+enum MsgRoot {
+    #[osc_address(adress="volume")]
+    Volume((), (f32,)),
+}
+
+fn main() {}